@@ -0,0 +1,37 @@
+//! Compares linear `Bucketizer` lookups against binary-search `SortedBucketizer`
+//! lookups over a large set of contiguous buckets. Run with `cargo bench`.
+
+extern crate bucketize;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bucketize::Bucketizer;
+
+const BUCKETS: u32 = 10_000;
+const LOOKUPS: u32 = 100_000;
+
+fn time<F: FnMut(f64) -> Option<f64>>(mut lookup: F) -> Duration {
+    let start = Instant::now();
+    for i in 0..LOOKUPS {
+        // Spread inputs across the whole range, including a little past each end.
+        let input = f64::from(i.wrapping_mul(7_919) % (BUCKETS + 200)) - 100.0;
+        black_box(lookup(black_box(input)));
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let mut linear = Bucketizer::new();
+    for i in 0..BUCKETS {
+        let min = f64::from(i);
+        linear.add_bucket(Some(min), Some(min + 1.0), min);
+    }
+    let sorted = linear.clone().sorted().unwrap();
+
+    for &(name, elapsed) in &[("linear", time(|input| linear.bucketize(input))),
+                              ("sorted", time(|input| sorted.bucketize(input)))] {
+        println!("{}: {} buckets, {:.1} ns/lookup",
+                 name, BUCKETS, elapsed.as_secs_f64() * 1e9 / f64::from(LOOKUPS));
+    }
+}
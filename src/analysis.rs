@@ -0,0 +1,784 @@
+//! Inspecting how a `Bucketizer`'s buckets cover the number line.
+
+use std::cmp::Ordering;
+
+use alloc::vec::Vec;
+
+use {BucketError, Bucketizer, Entry};
+
+impl<T: PartialOrd + Clone, V: Clone> Bucketizer<T, V> {
+    /// Find every pair of buckets that share at least one value, as
+    /// `(earlier, later)` indexes in the order buckets were added.
+    ///
+    /// Open bounds extend to infinity. Since buckets are max-exclusive by default,
+    /// buckets that only touch, like `[0, 10)` and `[10, 20)`, don't overlap.
+    /// Buckets that can never match anything don't overlap with anything either.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(10.0), Some(20.0), 15.0)
+    ///     .bucket(Some(15.0), None, 15.0);
+    ///
+    /// assert_eq!(b.overlaps(), vec![(1, 2)]);
+    /// ```
+    pub fn overlaps(&self) -> Vec<(usize, usize)> {
+        let mut overlaps = Vec::new();
+        for (i, first) in self.buckets.iter().enumerate() {
+            for (j, second) in self.buckets.iter().enumerate().skip(i + 1) {
+                if !first.is_empty() && !second.is_empty()
+                    && !first.starts_after(second) && !second.starts_after(first) {
+                    overlaps.push((i, j));
+                }
+            }
+        }
+        overlaps
+    }
+
+    /// The outer bounds of all buckets together, as `(min, max)`: the lowest `min`
+    /// and the highest `max`. Returns `None` if there are no buckets.
+    ///
+    /// If any bucket has an open `min`, so does the span, and likewise for `max`.
+    /// Buckets that can never match anything are ignored. The span can include
+    /// gaps between buckets; use `gaps()` to find them.
+    ///
+    /// An infinite `f64` bound counts as a bound like any other. Use
+    /// `extended_span()` to treat it as open instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(10.0), Some(20.0), 15.0)
+    ///     .bucket(Some(0.0), Some(5.0), 2.5);
+    ///
+    /// assert_eq!(b.span(), Some((Some(0.0), Some(20.0))));
+    /// ```
+    pub fn span(&self) -> Option<(Option<T>, Option<T>)> {
+        span_of(&self.analyzed_buckets(|_| None))
+    }
+
+    /// Every distinct `min` and `max` of the buckets, in ascending order, such as for
+    /// drawing axis ticks. Open bounds are left out, as are bounds that can't be
+    /// compared, like `f64::NAN`. An infinite `f64` bound is a bound like any other,
+    /// so it is included.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(10.0), None, 15.0)
+    ///     .bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.edges(), vec![0.0, 10.0]);
+    /// ```
+    pub fn edges(&self) -> Vec<T> {
+        let mut edges: Vec<T> = self.buckets.iter()
+            .flat_map(|entry| entry.bucket.0.iter().chain(entry.bucket.1.iter()))
+            .filter(|edge| edge.partial_cmp(edge).is_some())
+            .cloned()
+            .collect();
+        edges.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        edges.dedup();
+        edges
+    }
+
+    /// Check every bucket for problems that would stop it from ever matching, and
+    /// report them all in the order buckets were added.
+    ///
+    /// A bucket with a bound that can't be compared, like `f64::NAN`, is reported
+    /// as `BucketError::IncomparableBound`, one whose `min` is greater than its
+    /// `max` as `BucketError::MinExceedsMax`, and every bucket that a catch-all
+    /// bucket with no `min` or `max` takes precedence over as
+    /// `BucketError::Unreachable`: those after a catch-all of the same or higher
+    /// priority, and those anywhere before one of higher priority.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::{BucketError, Bucketizer};
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(None, None, 0.0)
+    ///     .bucket(Some(10.0), Some(20.0), 15.0);
+    ///
+    /// assert_eq!(b.validate(), Err(vec![BucketError::Unreachable { index: 2 }]));
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<BucketError>> {
+        let mut errors = Vec::new();
+        let incomparable = |bound: &Option<T>| bound.as_ref().is_some_and(|b| b.partial_cmp(b).is_none());
+        for (index, entry) in self.buckets.iter().enumerate() {
+            if self.is_shadowed(index) {
+                errors.push(BucketError::Unreachable { index });
+            } else if incomparable(&entry.bucket.0) || incomparable(&entry.bucket.1) {
+                errors.push(BucketError::IncomparableBound { index });
+            } else if let (Some(min), Some(max), _) = &entry.bucket {
+                if min > max {
+                    errors.push(BucketError::MinExceedsMax { index });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Find the ranges of values that fall into no bucket, as `(min, max)` pairs in
+    /// ascending order. `None` stands for negative infinity as a `min` and positive
+    /// infinity as a `max`.
+    ///
+    /// Each gap runs from where the buckets below it stop to where the buckets above
+    /// it start. For instance, with buckets `[0, 10)` and `[20, 30)`, every value from
+    /// 10 up to but not including 20 matches nothing, which is reported as
+    /// `(Some(10), Some(20))`. Whether a gap's endpoints are themselves covered
+    /// depends on the neighboring buckets, so a single uncovered point, like 10
+    /// between `[0, 10)` and `(10, 20)`, is reported as `(Some(10), Some(10))`.
+    ///
+    /// An infinite `f64` bound counts as a bound like any other, so a bucket ending
+    /// at an exclusive `f64::INFINITY` leaves infinity itself as a gap. Use
+    /// `extended_gaps()` to treat it as open instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(20.0), Some(30.0), 25.0);
+    ///
+    /// assert_eq!(b.gaps(), vec![
+    ///     (None, Some(0.0)),
+    ///     (Some(10.0), Some(20.0)),
+    ///     (Some(30.0), None),
+    /// ]);
+    /// ```
+    pub fn gaps(&self) -> Vec<(Option<T>, Option<T>)> {
+        gaps_of(&self.analyzed_buckets(|_| None))
+    }
+
+    /// Whether every value from `lo` up to but not including `hi` falls into some
+    /// bucket. An empty range, where `hi` isn't greater than `lo`, is always covered.
+    ///
+    /// Like `gaps()`, this treats bounds as points on a continuous number line, so
+    /// buckets `[0, 10]` and `[11, 20)` leave the values between 10 and 11 uncovered
+    /// even for integers. Also like `gaps()`, an infinite `f64` bound counts as a
+    /// bound like any other; use `extended_covers()` to treat it as open instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(20.0), Some(30.0), 25.0);
+    ///
+    /// assert!(b.covers(0.0, 10.0));
+    /// assert!(!b.covers(0.0, 25.0));
+    /// ```
+    pub fn covers(&self, lo: T, hi: T) -> bool {
+        covers_of(&self.analyzed_buckets(|_| None), lo, hi)
+    }
+
+    /// The buckets that can match anything, as the analysis helpers see them: with
+    /// every `min` that `infinity` reports as `Ordering::Less` and every `max` it
+    /// reports as `Ordering::Greater` made open.
+    fn analyzed_buckets(&self, infinity: fn(&T) -> Option<Ordering>) -> Vec<Entry<T, V>> {
+        self.buckets.iter()
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let mut entry = entry.clone();
+                if entry.bucket.0.as_ref().and_then(infinity) == Some(Ordering::Less) {
+                    entry.bucket.0 = None;
+                }
+                if entry.bucket.1.as_ref().and_then(infinity) == Some(Ordering::Greater) {
+                    entry.bucket.1 = None;
+                }
+                entry
+            })
+            .collect()
+    }
+}
+
+/// Order buckets by ascending `min`, with open `min`s first and, for the same
+/// `min`, an inclusive one before an exclusive one.
+fn by_min<T: PartialOrd, V>(a: &Entry<T, V>, b: &Entry<T, V>) -> Ordering {
+    match (&a.bucket.0, &b.bucket.0) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(a_min), Some(b_min)) => match a_min.partial_cmp(b_min) {
+            Some(Ordering::Equal) => b.min_inclusive.cmp(&a.min_inclusive),
+            ordering => ordering.unwrap_or(Ordering::Equal),
+        },
+    }
+}
+
+/// The outer bounds of `entries` together, as for `Bucketizer::span()`.
+fn span_of<T: PartialOrd + Clone, V>(entries: &[Entry<T, V>]) -> Option<(Option<T>, Option<T>)> {
+    let mut entries = entries.iter();
+    let first = entries.next()?;
+    let mut span = (first.bucket.0.clone(), first.bucket.1.clone());
+    for entry in entries {
+        span.0 = match (span.0, &entry.bucket.0) {
+            (Some(lowest), Some(min)) if min < &lowest => Some(min.clone()),
+            (Some(lowest), Some(_)) => Some(lowest),
+            _ => None,
+        };
+        span.1 = match (span.1, &entry.bucket.1) {
+            (Some(highest), Some(max)) if max > &highest => Some(max.clone()),
+            (Some(highest), Some(_)) => Some(highest),
+            _ => None,
+        };
+    }
+    Some(span)
+}
+
+/// The ranges of values that fall into none of `entries`, as for
+/// `Bucketizer::gaps()`.
+fn gaps_of<T: PartialOrd + Clone, V>(entries: &[Entry<T, V>]) -> Vec<(Option<T>, Option<T>)> {
+    let mut covered: Vec<_> = entries.iter().collect();
+    covered.sort_by(|a, b| by_min(a, b));
+
+    let mut gaps = Vec::new();
+    // The furthest point covered so far, and whether it is itself covered.
+    // `None` means everything below the next bucket's `min` is uncovered.
+    let mut reach: Option<(&T, bool)> = None;
+    for (i, entry) in covered.iter().enumerate() {
+        match (i, &entry.bucket.0, reach) {
+            (0, Some(min), _) => gaps.push((None, Some(min.clone()))),
+            (_, Some(min), Some((end, end_inclusive)))
+                if min > end || (min == end && !end_inclusive && !entry.min_inclusive) =>
+                gaps.push((Some(end.clone()), Some(min.clone()))),
+            _ => {}
+        }
+        match (&entry.bucket.1, reach) {
+            (None, _) => return gaps,
+            (Some(max), Some((end, end_inclusive)))
+                if max < end || (max == end && end_inclusive) => {}
+            (Some(max), _) => reach = Some((max, entry.max_inclusive)),
+        }
+    }
+    match reach {
+        Some((end, _)) => gaps.push((Some(end.clone()), None)),
+        None => gaps.push((None, None)),
+    }
+    gaps
+}
+
+/// Whether `entries` cover every value in `[lo, hi)`, as for `Bucketizer::covers()`.
+fn covers_of<T: PartialOrd + Clone, V>(entries: &[Entry<T, V>], lo: T, hi: T) -> bool {
+    if lo.partial_cmp(&hi) != Some(Ordering::Less) {
+        return true;
+    }
+    let mut covered: Vec<_> = entries.iter().collect();
+    covered.sort_by(|a, b| by_min(a, b));
+
+    // Everything from `lo` up to `reach` is covered, and `reach` itself is if
+    // `reach_covered` is set.
+    let mut reach = lo;
+    let mut reach_covered = false;
+    for entry in covered {
+        let picks_up = match entry.bucket.0 {
+            None => true,
+            Some(ref min) => match min.partial_cmp(&reach) {
+                Some(Ordering::Less) => true,
+                Some(Ordering::Equal) => reach_covered || entry.min_inclusive,
+                Some(Ordering::Greater) => return false,
+                None => false,
+            },
+        };
+        if !picks_up {
+            continue;
+        }
+        match entry.bucket.1 {
+            None => return true,
+            Some(ref max) => match max.partial_cmp(&reach) {
+                Some(Ordering::Greater) => {
+                    reach = max.clone();
+                    reach_covered = entry.max_inclusive;
+                }
+                Some(Ordering::Equal) => reach_covered = reach_covered || entry.max_inclusive,
+                _ => {}
+            },
+        }
+        if reach.partial_cmp(&hi) != Some(Ordering::Less) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether `bound` is infinite, as `Ordering::Less` for negative infinity and
+/// `Ordering::Greater` for positive infinity.
+fn infinity(bound: &f64) -> Option<Ordering> {
+    if bound.is_infinite() {
+        bound.partial_cmp(&0.0)
+    } else {
+        None
+    }
+}
+
+impl<V: Clone> Bucketizer<f64, V> {
+    /// Like `span()`, but on the extended real line, where a `min` of
+    /// `f64::NEG_INFINITY` or a `max` of `f64::INFINITY` is treated as open, since
+    /// no value lies beyond it. The buckets themselves are left as they are.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(f64::NEG_INFINITY), Some(0.0), -1.0)
+    ///     .bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.extended_span(), Some((None, Some(10.0))));
+    /// assert_eq!(b.span(), Some((Some(f64::NEG_INFINITY), Some(10.0))));
+    /// ```
+    pub fn extended_span(&self) -> Option<(Option<f64>, Option<f64>)> {
+        span_of(&self.analyzed_buckets(infinity))
+    }
+
+    /// Like `gaps()`, but with infinite bounds treated as open, as for
+    /// `extended_span()`. A bucket ending at an exclusive `f64::INFINITY` leaves no
+    /// gap above it, though `bucketize(f64::INFINITY)` still doesn't match it.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(f64::INFINITY), 1.0);
+    ///
+    /// assert_eq!(b.extended_gaps(), vec![(None, Some(0.0))]);
+    /// assert_eq!(b.gaps(), vec![(None, Some(0.0)), (Some(f64::INFINITY), None)]);
+    /// ```
+    pub fn extended_gaps(&self) -> Vec<(Option<f64>, Option<f64>)> {
+        gaps_of(&self.analyzed_buckets(infinity))
+    }
+
+    /// Like `covers()`, but with infinite bounds treated as open, as for
+    /// `extended_span()`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// use std::ops::Bound;
+    ///
+    /// let b = Bucketizer::new()
+    ///     .bucket_range((Bound::Excluded(f64::NEG_INFINITY), Bound::Unbounded), 0.0);
+    ///
+    /// assert!(b.extended_covers(f64::NEG_INFINITY, 0.0));
+    /// assert!(!b.covers(f64::NEG_INFINITY, 0.0));
+    /// ```
+    pub fn extended_covers(&self, lo: f64, hi: f64) -> bool {
+        covers_of(&self.analyzed_buckets(infinity), lo, hi)
+    }
+
+    /// The fraction of `[lo, hi)` covered by at least one bucket, from `0.0` to `1.0`.
+    ///
+    /// Buckets are clipped to `[lo, hi)`, with open bounds extending to its edges,
+    /// and overlapping buckets are only counted once. Returns `0.0` if `hi` isn't
+    /// greater than `lo`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(5.0), Some(15.0), 10.0);
+    ///
+    /// assert_eq!(b.coverage_fraction(0.0, 20.0), 0.75);
+    /// ```
+    pub fn coverage_fraction(&self, lo: f64, hi: f64) -> f64 {
+        if hi.partial_cmp(&lo) != Some(Ordering::Greater) {
+            return 0.0;
+        }
+        let mut spans: Vec<(f64, f64)> = self.buckets.iter()
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let start = match entry.bucket.0 {
+                    None => lo,
+                    Some(min) if min.is_nan() => return None,
+                    Some(min) => min.max(lo),
+                };
+                let end = match entry.bucket.1 {
+                    None => hi,
+                    Some(max) if max.is_nan() => return None,
+                    Some(max) => max.min(hi),
+                };
+                if start < end {
+                    Some((start, end))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut covered = 0.0;
+        let mut reach = lo;
+        for (start, end) in spans {
+            let start = start.max(reach);
+            if end > start {
+                covered += end - start;
+                reach = end;
+            }
+        }
+        covered / (hi - lo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {BucketError, Bucketizer};
+
+    #[test]
+    fn touching_buckets_do_not_overlap() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(10.0), Some(20.0), 15.0);
+
+        assert!(bucketizer.overlaps().is_empty());
+    }
+
+    #[test]
+    fn touching_inclusive_max_overlaps() {
+        let bucketizer = Bucketizer::new()
+            .bucket_inclusive(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(10.0), Some(20.0), 15.0);
+
+        assert_eq!(bucketizer.overlaps(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn true_overlaps() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(20), Some(30), 25)
+            .bucket(Some(5), Some(25), 15)
+            .bucket(None, Some(1), 0);
+
+        assert_eq!(bucketizer.overlaps(), vec![(0, 2), (0, 3), (1, 2)]);
+    }
+
+    #[test]
+    fn disjoint_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(20), None, 25)
+            .bucket(None, Some(0), -5)
+            .bucket(Some(5), Some(10), 7)
+            .bucket(Some(10), Some(5), 7);
+
+        assert!(bucketizer.overlaps().is_empty());
+    }
+
+    #[test]
+    fn open_buckets_overlap_everything() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, None, 0)
+            .bucket(Some(1000), None, 1);
+
+        assert_eq!(bucketizer.overlaps(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn gaps_between_disjoint_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(40), Some(50), 45)
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(20), Some(30), 25);
+
+        assert_eq!(bucketizer.gaps(), vec![
+            (None, Some(0)),
+            (Some(10), Some(20)),
+            (Some(30), Some(40)),
+            (Some(50), None),
+        ]);
+    }
+
+    #[test]
+    fn gaps_merge_overlapping_and_touching_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(5), Some(15), 10)
+            .bucket(Some(15), Some(20), 18)
+            .bucket(Some(2), Some(3), 0)
+            .bucket_inclusive(Some(25), Some(30), 27)
+            .bucket(Some(30), Some(35), 32);
+
+        assert_eq!(bucketizer.gaps(), vec![
+            (None, Some(0)),
+            (Some(20), Some(25)),
+            (Some(35), None),
+        ]);
+    }
+
+    #[test]
+    fn no_gaps_when_fully_covered() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), None, 1.0)
+            .bucket(None, Some(0.0), -1.0);
+
+        assert!(bucketizer.gaps().is_empty());
+        assert!(Bucketizer::<i32>::new().bucket(None, None, 0).gaps().is_empty());
+    }
+
+    #[test]
+    fn empty_bucketizer_is_one_gap() {
+        assert_eq!(Bucketizer::<f64>::new().gaps(), vec![(None, None)]);
+    }
+
+    #[test]
+    fn empty_buckets_cover_nothing() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(20), Some(20), 20)
+            .bucket(Some(30), None, 35);
+
+        assert_eq!(bucketizer.gaps(), vec![(None, Some(0)), (Some(10), Some(30))]);
+        assert_eq!(Bucketizer::new().bucket(Some(5), Some(5), 5).gaps(), vec![(None, None)]);
+    }
+
+    #[test]
+    fn single_point_gap() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket_range((Bound::Excluded(10), Bound::Unbounded), 15)
+            .bucket_range((Bound::Excluded(-5), Bound::Excluded(0)), -2);
+
+        assert_eq!(bucketizer.gaps(), vec![(None, Some(-5)), (Some(10), Some(10))]);
+    }
+
+    #[test]
+    fn exclusive_edges_never_overlap_when_touching() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .bucket_inclusive(Some(0), Some(10), 5)
+            .bucket_range((Bound::Excluded(10), Bound::Included(20)), 15);
+
+        assert!(bucketizer.overlaps().is_empty());
+        let point = Bucketizer::new().bucket_range((Bound::Excluded(3), Bound::Included(3)), 3);
+        assert_eq!(point.gaps(), vec![(None, None)]);
+    }
+
+    #[test]
+    fn fully_covered() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(5.0), 0.0)
+            .bucket(Some(3.0), None, 1.0);
+
+        assert_eq!(bucketizer.coverage_fraction(0.0, 10.0), 1.0);
+        assert_eq!(Bucketizer::new().bucket(None, None, 0.0).coverage_fraction(-1e9, 1e9), 1.0);
+    }
+
+    #[test]
+    fn half_covered() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(3.0), 0.0)
+            .bucket(Some(1.0), Some(2.0), 1.0)
+            .bucket(Some(8.0), Some(12.0), 2.0)
+            .bucket(Some(-5.0), Some(-1.0), 3.0);
+
+        assert_eq!(bucketizer.coverage_fraction(0.0, 10.0), 0.5);
+    }
+
+    #[test]
+    fn uncovered() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(20.0), None, 0.0)
+            .bucket(Some(5.0), Some(5.0), 1.0);
+
+        assert_eq!(bucketizer.coverage_fraction(0.0, 10.0), 0.0);
+        assert_eq!(Bucketizer::<f64>::new().coverage_fraction(0.0, 10.0), 0.0);
+        assert_eq!(bucketizer.coverage_fraction(10.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn validate_flags_buckets_after_catch_all() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, None, 0)
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(20), Some(10), 15);
+
+        assert_eq!(bucketizer.validate(), Err(vec![
+            BucketError::Unreachable { index: 1 },
+            BucketError::Unreachable { index: 2 },
+        ]));
+    }
+
+    #[test]
+    fn validate_reversed_bounds() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(0), 5)
+            .bucket(None, Some(0), -5)
+            .bucket(Some(0), None, 5);
+
+        assert_eq!(bucketizer.validate(), Err(vec![BucketError::MinExceedsMax { index: 0 }]));
+        assert_eq!(Bucketizer::new().bucket(Some(0), Some(10), 5).validate(), Ok(()));
+    }
+
+    #[test]
+    fn span_of_bounded_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(-5), Some(0), -2)
+            .bucket(Some(30), Some(0), 0)
+            .bucket(Some(40), Some(50), 45);
+
+        assert_eq!(bucketizer.span(), Some((Some(-5), Some(50))));
+        assert_eq!(Bucketizer::<i32>::new().span(), None);
+    }
+
+    #[test]
+    fn span_with_open_end() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(20), None, 25)
+            .bucket(Some(0), Some(10), 5);
+
+        assert_eq!(bucketizer.span(), Some((Some(0), None)));
+        assert_eq!(bucketizer.bucket(None, Some(-10), -10).span(), Some((None, None)));
+    }
+
+    #[test]
+    fn covers_fully_covered_range() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(-5), Some(5), 0)
+            .bucket_inclusive(Some(0), Some(10), 5)
+            .bucket_range((Bound::Excluded(20), Bound::Excluded(30)), 25)
+            .bucket_inclusive(Some(20), Some(20), 20);
+
+        assert!(bucketizer.covers(-5, 30));
+        assert!(bucketizer.covers(12, 13));
+        assert!(bucketizer.covers(40, 40));
+        assert!(Bucketizer::new().bucket(None, None, 0).covers(-100, 100));
+    }
+
+    #[test]
+    fn covers_detects_hole() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket_range((Bound::Excluded(10.0), Bound::Excluded(20.0)), 15.0)
+            .bucket(Some(25.0), None, 30.0);
+
+        assert!(!bucketizer.covers(0.0, 20.0));
+        assert!(bucketizer.covers(0.0, 10.0));
+        assert!(!bucketizer.covers(15.0, 30.0));
+        assert!(!bucketizer.covers(-1.0, 5.0));
+        assert!(bucketizer.covers(25.0, 1e9));
+    }
+
+    #[test]
+    fn validate_allows_higher_priority_after_catch_all() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, None, 0)
+            .bucket_with_priority(Some(0), Some(10), 5, 1)
+            .bucket(Some(10), Some(20), 15);
+
+        assert_eq!(bucketizer.validate(), Err(vec![BucketError::Unreachable { index: 2 }]));
+    }
+
+    #[test]
+    fn validate_flags_incomparable_bounds() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(f64::NAN), Some(10.0), 5.0)
+            .bucket(Some(10.0), Some(20.0), 15.0)
+            .bucket(Some(20.0), Some(f64::NAN), 25.0);
+
+        assert_eq!(bucketizer.validate(), Err(vec![
+            BucketError::IncomparableBound { index: 0 },
+            BucketError::IncomparableBound { index: 2 },
+        ]));
+    }
+
+    #[test]
+    fn edges_of_contiguous_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(0), Some(10), 5);
+
+        assert_eq!(bucketizer.edges(), vec![0, 10, 20]);
+        assert_eq!(bucketizer.bucket(Some(20), None, 25).bucket(None, Some(-5), -5).edges(), vec![-5, 0, 10, 20]);
+        assert!(Bucketizer::new().bucket(None, Some(f64::NAN), 0.0).edges().is_empty());
+    }
+
+    #[test]
+    fn edges_keep_infinities_and_skip_nan() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(f64::NEG_INFINITY), Some(0.0), -1.0)
+            .bucket(Some(0.0), Some(f64::NAN), 0.5)
+            .bucket(Some(10.0), Some(f64::INFINITY), 15.0);
+
+        assert_eq!(bucketizer.edges(), vec![f64::NEG_INFINITY, 0.0, 10.0, f64::INFINITY]);
+    }
+
+    #[test]
+    fn analysis_helpers_work_for_borrowed_bounds() {
+        fn gaps<'a>(words: &[&'a str]) -> Vec<(Option<&'a str>, Option<&'a str>)> {
+            Bucketizer::new().bucket(Some(words[0]), Some(words[1]), 0).gaps()
+        }
+
+        let words = [String::from("apple"), String::from("melon")];
+        assert_eq!(gaps(&[&words[0], &words[1]]), vec![(None, Some("apple")), (Some("melon"), None)]);
+    }
+
+    #[test]
+    fn extended_helpers_treat_infinities_as_open() {
+        let infinite = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(20.0), Some(f64::INFINITY), 25.0);
+        let open = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(20.0), None, 25.0);
+
+        assert_eq!(infinite.extended_gaps(), open.gaps());
+        assert_eq!(infinite.extended_gaps(), vec![(None, Some(0.0)), (Some(10.0), Some(20.0))]);
+        assert_eq!(infinite.extended_span(), open.span());
+        assert!(infinite.extended_covers(20.0, f64::INFINITY));
+        assert_eq!(infinite.bucketize(f64::INFINITY), None);
+        assert_eq!(infinite.gaps().last(), Some(&(Some(f64::INFINITY), None)));
+    }
+
+    #[test]
+    fn negative_infinity_is_open_min() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(f64::NEG_INFINITY), Some(0.0), "negative")
+            .bucket(Some(0.0), Some(f64::INFINITY), "positive")
+            .bucket(Some(f64::INFINITY), None, "infinite");
+
+        assert!(bucketizer.extended_gaps().is_empty());
+        assert_eq!(bucketizer.extended_span(), Some((None, None)));
+        assert!(bucketizer.extended_covers(f64::NEG_INFINITY, 1e300));
+        assert_eq!(Bucketizer::new().bucket(Some(f64::INFINITY), None, 0.0).extended_span(),
+                   Some((Some(f64::INFINITY), None)));
+    }
+
+    #[test]
+    fn validate_flags_buckets_before_higher_priority_catch_all() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket_with_priority(Some(10), Some(20), 15, 2)
+            .bucket_with_priority(None, None, 0, 1);
+
+        assert_eq!(bucketizer.validate(), Err(vec![BucketError::Unreachable { index: 0 }]));
+    }
+
+    #[test]
+    fn gaps_prefer_inclusive_min_on_ties() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket_range((Bound::Excluded(10), Bound::Excluded(20)), 15)
+            .bucket(Some(10), Some(15), 12);
+
+        assert_eq!(bucketizer.gaps(), vec![(None, Some(0)), (Some(20), None)]);
+    }
+}
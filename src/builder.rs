@@ -0,0 +1,128 @@
+//! Building a `Bucketizer` all at once, checking every bucket before use.
+
+use alloc::vec::Vec;
+
+use {BucketError, Bucketizer};
+
+/// Collects buckets and checks them all together when built, created by
+/// `Bucketizer::builder()`.
+///
+/// Where `Bucketizer::try_bucket()` stops at the first bad bucket, `build()`
+/// reports every problem at once, which suits checking a whole configuration
+/// loaded from elsewhere.
+///
+/// # Example
+/// ```
+/// # use bucketize::{BucketError, Bucketizer};
+/// let b = Bucketizer::builder()
+///     .push(Some(0.0), Some(10.0), 5.0)
+///     .push(Some(10.0), None, 15.0)
+///     .build()
+///     .unwrap();
+/// assert_eq!(b.bucketize(12.34), Some(15.0));
+///
+/// let errors = Bucketizer::builder()
+///     .push(Some(10.0), Some(0.0), 5.0)
+///     .push(Some(f64::NAN), None, 15.0)
+///     .build()
+///     .unwrap_err();
+/// assert_eq!(errors, vec![
+///     BucketError::MinExceedsMax { index: 0 },
+///     BucketError::IncomparableBound { index: 1 },
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BucketizerBuilder<T: PartialOrd + Clone, V: Clone = T> {
+    inner: Bucketizer<T, V>,
+}
+
+impl<T: PartialOrd + Clone, V: Clone> Bucketizer<T, V> {
+    /// Start building a bucketizer whose buckets are all checked at once by
+    /// `BucketizerBuilder::build()`.
+    pub fn builder() -> BucketizerBuilder<T, V> {
+        BucketizerBuilder::new()
+    }
+}
+
+impl<T: PartialOrd + Clone, V: Clone> BucketizerBuilder<T, V> {
+    /// Create a new builder with no buckets.
+    pub fn new() -> Self {
+        BucketizerBuilder {
+            inner: Bucketizer::new(),
+        }
+    }
+
+    /// Add a new bucket, min-inclusive and max-exclusive like `Bucketizer::bucket()`.
+    /// It isn't checked until `build()`.
+    pub fn push(self, min: Option<T>, max: Option<T>, value: V) -> Self {
+        BucketizerBuilder {
+            inner: self.inner.bucket(min, max, value),
+        }
+    }
+
+    /// Check every bucket with `Bucketizer::validate()`, and return the finished
+    /// `Bucketizer` if none has a problem, or every problem found in the order
+    /// buckets were added.
+    pub fn build(self) -> Result<Bucketizer<T, V>, Vec<BucketError>> {
+        self.inner.validate().map(|()| self.inner)
+    }
+}
+
+impl<T: PartialOrd + Clone, V: Clone> Default for BucketizerBuilder<T, V> {
+    /// Equivalent to `BucketizerBuilder::new()`.
+    fn default() -> Self {
+        BucketizerBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {BucketError, Bucketizer};
+
+    #[test]
+    fn clean_build_matches_chained_buckets() {
+        let built = Bucketizer::builder()
+            .push(None, Some(0.0), -1.0)
+            .push(Some(0.0), Some(10.0), 5.0)
+            .push(Some(10.0), None, 15.0)
+            .build();
+        let chained = Bucketizer::new()
+            .bucket(None, Some(0.0), -1.0)
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(10.0), None, 15.0);
+
+        assert_eq!(built, Ok(chained));
+    }
+
+    #[test]
+    fn build_collects_every_error() {
+        let result = Bucketizer::builder()
+            .push(Some(0.0), Some(10.0), 5.0)
+            .push(Some(20.0), Some(10.0), 15.0)
+            .push(None, Some(f64::NAN), 25.0)
+            .push(None, None, 0.0)
+            .push(Some(30.0), None, 35.0)
+            .build();
+
+        assert_eq!(result, Err(vec![
+            BucketError::MinExceedsMax { index: 1 },
+            BucketError::IncomparableBound { index: 2 },
+            BucketError::Unreachable { index: 4 },
+        ]));
+    }
+
+    #[test]
+    fn build_agrees_with_validate() {
+        let built = Bucketizer::builder()
+            .push(Some(5), Some(0), 0)
+            .push(None, None, 1)
+            .push(Some(10), None, 2)
+            .build();
+        let chained = Bucketizer::new()
+            .bucket(Some(5), Some(0), 0)
+            .bucket(None, None, 1)
+            .bucket(Some(10), None, 2);
+
+        assert_eq!(built.map(|_| ()), chained.validate());
+    }
+}
@@ -0,0 +1,268 @@
+//! Matching buckets with a custom ordering instead of `PartialOrd`.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeBounds;
+
+use {Bucketizer, Entry};
+
+/// A `Bucketizer` that compares inputs to bucket bounds with a custom comparator,
+/// created by `Bucketizer::with_comparator()`.
+///
+/// Buckets are added and evaluated exactly as for a `Bucketizer`, except that a
+/// value is above `min` or below `max` according to the comparator rather than
+/// the type's own `PartialOrd`. An input that can't be compared to itself with
+/// `PartialOrd`, like `f64::NAN`, is never passed to the comparator, and only falls
+/// into buckets with both bounds open, just as with `Bucketizer::bucketize()`.
+///
+/// # Example
+/// ```
+/// use bucketize::Bucketizer;
+///
+/// let b = Bucketizer::with_comparator(|a: &f64, b: &f64| a.abs().partial_cmp(&b.abs()).unwrap())
+///     .bucket(Some(0.0), Some(5.0), "small")
+///     .bucket(Some(5.0), None, "large");
+///
+/// assert_eq!(b.bucketize(-7.0), Some("large"));
+/// assert_eq!(b.bucketize(3.0), Some("small"));
+/// ```
+#[derive(Clone)]
+pub struct ComparatorBucketizer<T: PartialOrd + Clone, V: Clone, F> {
+    inner: Bucketizer<T, V>,
+    cmp: F,
+}
+
+impl<T: PartialOrd + Clone, V: Clone> Bucketizer<T, V> {
+    /// Create a new bucketizer with no buckets configured, which orders inputs with
+    /// `cmp` instead of `PartialOrd`.
+    pub fn with_comparator<F: Fn(&T, &T) -> Ordering>(cmp: F) -> ComparatorBucketizer<T, V, F> {
+        ComparatorBucketizer {
+            inner: Bucketizer::new(),
+            cmp,
+        }
+    }
+}
+
+impl<V: Clone> Bucketizer<f64, V> {
+    /// Create a new bucketizer with no buckets configured, which treats an input
+    /// within `eps` of a bound as equal to it, to absorb floating-point rounding.
+    ///
+    /// At each edge, an input within `eps` of the bound falls on whichever side of
+    /// it is inclusive: into a bucket whose `min` or `max` is inclusive, and out of
+    /// one where it is exclusive. So with buckets `[0, 0.3)` and `[0.3, 0.6)`, an
+    /// input just below `0.3` falls into the second bucket, and with an inclusive
+    /// `max` of `0.3` it falls into the bucket ending there even if just above it.
+    /// Inputs further than `eps` from a bound are compared as usual.
+    ///
+    /// The tolerance never pulls an input into a bucket across an exclusive bound.
+    /// A bucket added with `bucket()` has an exclusive `max`, so an input just above
+    /// it, like `0.3 + 1e-12` for a `max` of `0.3`, still falls into the next bucket
+    /// or none at all. Only a bucket added with `bucket_inclusive()`, or with an
+    /// inclusive `max` through `bucket_range()`, absorbs inputs just past its `max`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::with_tolerance(1e-9)
+    ///     .bucket_inclusive(Some(0.1), Some(0.3), "low")
+    ///     .bucket(Some(0.3), None, "high");
+    ///
+    /// assert_eq!(b.bucketize(0.1 + 0.2), Some("low"));
+    /// ```
+    pub fn with_tolerance(eps: f64) -> ComparatorBucketizer<f64, V, impl Fn(&f64, &f64) -> Ordering> {
+        Bucketizer::with_comparator(move |input: &f64, bound: &f64| {
+            if (input - bound).abs() <= eps {
+                Ordering::Equal
+            } else {
+                input.total_cmp(bound)
+            }
+        })
+    }
+}
+
+impl<T: PartialOrd + Clone, V: Clone, F: Fn(&T, &T) -> Ordering> ComparatorBucketizer<T, V, F> {
+    /// Add a new bucket, exactly like `Bucketizer::bucket()`.
+    pub fn bucket(self, min: Option<T>, max: Option<T>, value: V) -> Self {
+        let mut new = self;
+        new.add_bucket(min, max, value);
+        new
+    }
+
+    /// Add a new bucket in place, exactly like `Bucketizer::add_bucket()`.
+    pub fn add_bucket(&mut self, min: Option<T>, max: Option<T>, value: V) {
+        self.inner.add_bucket(min, max, value);
+    }
+
+    /// Add a new bucket whose `max` is inclusive, exactly like
+    /// `Bucketizer::bucket_inclusive()`.
+    pub fn bucket_inclusive(self, min: Option<T>, max: Option<T>, value: V) -> Self {
+        ComparatorBucketizer {
+            inner: self.inner.bucket_inclusive(min, max, value),
+            cmp: self.cmp,
+        }
+    }
+
+    /// Add a new bucket covering `range`, exactly like `Bucketizer::bucket_range()`.
+    pub fn bucket_range<R: RangeBounds<T>>(self, range: R, value: V) -> Self {
+        ComparatorBucketizer {
+            inner: self.inner.bucket_range(range, value),
+            cmp: self.cmp,
+        }
+    }
+
+    /// Get the bucketized value of `input`, comparing it to each bucket's bounds
+    /// with the comparator.
+    pub fn bucketize(&self, input: T) -> Option<V> {
+        self.bucketize_index(input).map(|index| self.inner.buckets[index].bucket.2.clone())
+    }
+
+    /// Get the index of the first bucket `input` falls into, comparing it to each
+    /// bucket's bounds with the comparator.
+    pub fn bucketize_index(&self, input: T) -> Option<usize> {
+        let comparable = input.partial_cmp(&input).is_some();
+        self.inner.buckets.iter().position(|entry| {
+            entry.is_catch_all() || (comparable && contains_by(entry, &input, &self.cmp))
+        })
+    }
+
+    /// The number of buckets configured.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether no buckets have been configured.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Convert into a plain `Bucketizer` with the same buckets, which orders inputs
+    /// with `PartialOrd`.
+    pub fn into_inner(self) -> Bucketizer<T, V> {
+        self.inner
+    }
+}
+
+impl<T, V, F> fmt::Debug for ComparatorBucketizer<T, V, F>
+    where T: PartialOrd + Clone + fmt::Debug,
+          V: Clone + fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ComparatorBucketizer")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether `input` falls into `entry`, according to `cmp`.
+fn contains_by<T, V, F: Fn(&T, &T) -> Ordering>(entry: &Entry<T, V>, input: &T, cmp: &F) -> bool {
+    let above_min = match entry.bucket.0 {
+        None => true,
+        Some(ref min) => match cmp(input, min) {
+            Ordering::Greater => true,
+            Ordering::Equal => entry.min_inclusive,
+            Ordering::Less => false,
+        },
+    };
+    let below_max = match entry.bucket.1 {
+        None => true,
+        Some(ref max) => match cmp(input, max) {
+            Ordering::Less => true,
+            Ordering::Equal => entry.max_inclusive,
+            Ordering::Greater => false,
+        },
+    };
+    above_min && below_max
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use Bucketizer;
+
+    fn by_magnitude(a: &f64, b: &f64) -> Ordering {
+        a.abs().partial_cmp(&b.abs()).unwrap()
+    }
+
+    #[test]
+    fn buckets_by_magnitude() {
+        let bucketizer = Bucketizer::with_comparator(by_magnitude)
+            .bucket(Some(0.0), Some(5.0), 1)
+            .bucket(Some(5.0), Some(10.0), 2)
+            .bucket(Some(10.0), None, 3);
+
+        assert_eq!(bucketizer.bucketize(-7.0), Some(2));
+        assert_eq!(bucketizer.bucketize(7.0), Some(2));
+        assert_eq!(bucketizer.bucketize(-3.0), Some(1));
+        assert_eq!(bucketizer.bucketize(-12.0), Some(3));
+        assert_eq!(Bucketizer::new().bucket(Some(0.0), Some(5.0), 1).bucketize(-3.0), None);
+    }
+
+    #[test]
+    fn comparator_respects_inclusivity() {
+        let bucketizer = Bucketizer::with_comparator(by_magnitude)
+            .bucket(Some(0.0), Some(5.0), 1)
+            .bucket_inclusive(Some(5.0), Some(10.0), 2)
+            .bucket_range(10.0.., 3);
+
+        assert_eq!(bucketizer.bucketize(-5.0), Some(2));
+        assert_eq!(bucketizer.bucketize(-10.0), Some(2));
+        assert_eq!(bucketizer.bucketize_index(10.5), Some(2));
+        assert_eq!(bucketizer.len(), 3);
+    }
+
+    #[test]
+    fn reversed_ordering() {
+        let bucketizer = Bucketizer::with_comparator(|a: &i32, b: &i32| b.cmp(a))
+            .bucket(Some(10), Some(0), "high")
+            .bucket(Some(0), None, "low");
+
+        assert_eq!(bucketizer.bucketize(7), Some("high"));
+        assert_eq!(bucketizer.bucketize(0), Some("low"));
+        assert_eq!(bucketizer.bucketize(11), None);
+    }
+
+    #[test]
+    fn incomparable_inputs_only_match_catch_all() {
+        let bucketizer = Bucketizer::with_comparator(by_magnitude)
+            .bucket(None, Some(5.0), 1)
+            .bucket(None, None, 2);
+
+        assert_eq!(bucketizer.bucketize(f64::NAN), Some(2));
+    }
+
+    #[test]
+    fn tolerance_includes_value_just_past_inclusive_max() {
+        let bucketizer = Bucketizer::with_tolerance(1e-9)
+            .bucket_inclusive(Some(0.1), Some(0.3), "low")
+            .bucket(Some(0.5), None, "high");
+
+        assert_eq!(bucketizer.bucketize(0.3 + 1e-12), Some("low"));
+        assert_eq!(bucketizer.bucketize(0.3 + 1e-6), None);
+        assert_eq!(bucketizer.bucketize(0.1 - 1e-12), Some("low"));
+        assert_eq!(Bucketizer::new().bucket_inclusive(Some(0.1), Some(0.3), "low").bucketize(0.3 + 1e-12), None);
+    }
+
+    #[test]
+    fn tolerance_respects_exclusive_edges() {
+        let bucketizer = Bucketizer::with_tolerance(1e-9)
+            .bucket(Some(0.0), Some(0.3), 1)
+            .bucket(Some(0.3), Some(0.6), 2);
+
+        assert_eq!(bucketizer.bucketize(0.3 - 1e-12), Some(2));
+        assert_eq!(bucketizer.bucketize(0.2999), Some(1));
+        assert_eq!(bucketizer.bucketize(0.6 - 1e-12), None);
+        assert_eq!(bucketizer.bucketize(f64::NAN), None);
+    }
+
+    #[test]
+    fn tolerance_does_not_extend_exclusive_max() {
+        let bucketizer = Bucketizer::with_tolerance(1e-9)
+            .bucket(Some(0.0), Some(0.3), 1)
+            .bucket(Some(0.5), None, 2);
+
+        assert_eq!(bucketizer.bucketize(0.3 + 1e-12), None);
+        assert_eq!(bucketizer.bucketize(0.3), None);
+        assert_eq!(bucketizer.bucket(Some(0.3), Some(0.5), 3).bucketize(0.3 + 1e-12), Some(3));
+    }
+}
@@ -0,0 +1,136 @@
+//! Reading and writing buckets as CSV rows, for review in a spreadsheet.
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use alloc::string::String;
+
+use {Bucketizer, ParseError};
+
+impl<T, V> Bucketizer<T, V>
+    where T: PartialOrd + Clone + fmt::Display,
+          V: Clone + fmt::Display
+{
+    /// Write the buckets as CSV, with a `min,max,value` header followed by one row
+    /// per bucket in evaluation order. Open bounds are left as empty cells.
+    ///
+    /// Cells aren't quoted, so this is meant for numbers and simple labels, not
+    /// values containing commas or line breaks. Like `to_dsl()`, it doesn't record
+    /// whether a bound is inclusive or exclusive.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(10.0), None, 10.5);
+    ///
+    /// assert_eq!(b.to_csv(), "min,max,value\n0,10,5\n10,,10.5\n");
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("min,max,value\n");
+        for (min, max, value) in self.iter() {
+            if let Some(min) = min {
+                write!(csv, "{}", min).unwrap();
+            }
+            csv.push(',');
+            if let Some(max) = max {
+                write!(csv, "{}", max).unwrap();
+            }
+            writeln!(csv, ",{}", value).unwrap();
+        }
+        csv
+    }
+}
+
+impl<T, V> Bucketizer<T, V>
+    where T: PartialOrd + Clone + FromStr,
+          V: Clone + FromStr
+{
+    /// Read buckets from CSV with one `min,max,value` row per bucket, evaluated in
+    /// the order they are listed, as written by `to_csv()`. An empty `min` or `max`
+    /// is open, and buckets are min-inclusive and max-exclusive, like ones added
+    /// with `bucket()`.
+    ///
+    /// A `min,max,value` header row is skipped if present, as are blank lines.
+    /// Whitespace around each cell is ignored.
+    ///
+    /// Returns `ParseError::WrongCellCount` for a row without exactly three cells,
+    /// and `ParseError::InvalidNumber` for a cell that doesn't parse.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::<f64>::from_csv("min,max,value\n0,10,5\n10,,10.5\n").unwrap();
+    ///
+    /// assert_eq!(b.bucketize(12.34), Some(10.5));
+    /// ```
+    pub fn from_csv(text: &str) -> Result<Self, ParseError> {
+        let mut rows = text.lines().map(str::trim).filter(|row| !row.is_empty()).peekable();
+        if let Some(header) = rows.peek() {
+            if header.split(',').map(str::trim).eq(["min", "max", "value"]) {
+                rows.next();
+            }
+        }
+        let mut bucketizer = Bucketizer::new();
+        for (index, row) in rows.enumerate() {
+            let mut cells = row.split(',').map(str::trim);
+            let (min, max, value) = match (cells.next(), cells.next(), cells.next(), cells.next()) {
+                (Some(min), Some(max), Some(value), None) => (min, max, value),
+                _ => return Err(ParseError::WrongCellCount { index }),
+            };
+            let value = value.parse().map_err(|_| ParseError::InvalidNumber { index })?;
+            bucketizer.add_bucket(parse_cell(min, index)?, parse_cell(max, index)?, value);
+        }
+        Ok(bucketizer)
+    }
+}
+
+/// Parse a `min` or `max` cell, where an empty cell is open.
+fn parse_cell<T: FromStr>(cell: &str, index: usize) -> Result<Option<T>, ParseError> {
+    match cell {
+        "" => Ok(None),
+        cell => cell.parse()
+            .map(Some)
+            .map_err(|_| ParseError::InvalidNumber { index }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Bucketizer, ParseError};
+
+    #[test]
+    fn to_csv_leaves_open_bounds_empty() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0), "negative")
+            .bucket(Some(0), Some(100), "small")
+            .bucket(Some(100), None, "large");
+
+        assert_eq!(bucketizer.to_csv(), "min,max,value\n,0,negative\n0,100,small\n100,,large\n");
+        assert_eq!(Bucketizer::<i32>::new().to_csv(), "min,max,value\n");
+    }
+
+    #[test]
+    fn from_csv_reads_rows_in_order() {
+        let csv = "min, max, value\n,0,-1\n0,10,5\n\n 10 , , 15 \n";
+        let bucketizer = Bucketizer::<i32>::from_csv(csv).unwrap();
+
+        assert_eq!(bucketizer, Bucketizer::new()
+            .bucket(None, Some(0), -1)
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), None, 15));
+        assert_eq!(Bucketizer::<i32>::from_csv(&bucketizer.to_csv()), Ok(bucketizer));
+        assert_eq!(Bucketizer::<f64, String>::from_csv("0,1.5,low").unwrap().bucketize(1.0), Some(String::from("low")));
+        assert!(Bucketizer::<f64>::from_csv("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn from_csv_rejects_malformed_rows() {
+        assert_eq!(Bucketizer::<f64>::from_csv("min,max,value\n0,10,5\n10,2O,15\n"),
+                   Err(ParseError::InvalidNumber { index: 1 }));
+        assert_eq!(Bucketizer::<f64>::from_csv("0,10,"), Err(ParseError::InvalidNumber { index: 0 }));
+        assert_eq!(Bucketizer::<f64>::from_csv("0,10,5\n10,20"), Err(ParseError::WrongCellCount { index: 1 }));
+        assert_eq!(Bucketizer::<f64>::from_csv("0,10,5,1"), Err(ParseError::WrongCellCount { index: 0 }));
+    }
+}
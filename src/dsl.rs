@@ -0,0 +1,131 @@
+//! Reading and writing a compact text definition of `f64` buckets.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use alloc::string::String;
+
+use {Bucketizer, ParseError};
+
+impl FromStr for Bucketizer<f64> {
+    type Err = ParseError;
+
+    /// Parse buckets written as `min:max=value`, separated by `;` and evaluated in
+    /// the order they are listed. An empty `min` or `max` is open, and buckets are
+    /// min-inclusive and max-exclusive, like ones added with `bucket()`.
+    ///
+    /// Whitespace around each field is ignored, as are empty entries, so a trailing
+    /// `;` is allowed.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b: Bucketizer<f64> = "0:10=5;10:20=15;:0=-1".parse().unwrap();
+    ///
+    /// assert_eq!(b.bucketize(12.34), Some(15.0));
+    /// assert_eq!(b.bucketize(-3.0), Some(-1.0));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut bucketizer = Bucketizer::new();
+        for (index, definition) in s.split(';').map(str::trim).filter(|d| !d.is_empty()).enumerate() {
+            let (range, value) = definition.split_once('=')
+                .ok_or(ParseError::MissingValue { index })?;
+            let (min, max) = range.split_once(':')
+                .ok_or(ParseError::MissingSeparator { index })?;
+            let value = value.trim().parse()
+                .map_err(|_| ParseError::InvalidNumber { index })?;
+            bucketizer.add_bucket(parse_bound(min, index)?, parse_bound(max, index)?, value);
+        }
+        Ok(bucketizer)
+    }
+}
+
+impl Bucketizer<f64> {
+    /// Write the buckets in the text form `from_str()` parses, as `min:max=value`
+    /// entries separated by `;`, with open bounds left empty.
+    ///
+    /// The text form can't say whether a bound is inclusive or exclusive, so every
+    /// bucket reads back in min-inclusive and max-exclusive.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(10.0), None, 10.5);
+    ///
+    /// assert_eq!(b.to_dsl(), "0:10=5;10:=10.5");
+    /// ```
+    pub fn to_dsl(&self) -> String {
+        let mut dsl = String::new();
+        for (i, (min, max, value)) in self.iter().enumerate() {
+            if i > 0 {
+                dsl.push(';');
+            }
+            if let Some(min) = min {
+                write!(dsl, "{}", min).unwrap();
+            }
+            dsl.push(':');
+            if let Some(max) = max {
+                write!(dsl, "{}", max).unwrap();
+            }
+            write!(dsl, "={}", value).unwrap();
+        }
+        dsl
+    }
+}
+
+/// Parse a `min` or `max`, where an empty field is open.
+fn parse_bound(bound: &str, index: usize) -> Result<Option<f64>, ParseError> {
+    match bound.trim() {
+        "" => Ok(None),
+        bound => bound.parse()
+            .map(Some)
+            .map_err(|_| ParseError::InvalidNumber { index }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Bucketizer, ParseError};
+
+    #[test]
+    fn parses_buckets_in_order() {
+        let bucketizer: Bucketizer<f64> = "0:10=5;10:20=15;5:15=-1".parse().unwrap();
+
+        assert_eq!(bucketizer, Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(10.0), Some(20.0), 15.0)
+            .bucket(Some(5.0), Some(15.0), -1.0));
+    }
+
+    #[test]
+    fn parses_open_bounds() {
+        let bucketizer: Bucketizer<f64> = " :0 = -1 ; 0.5: = 1e3 ; : = 0 ;".parse().unwrap();
+
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), vec![
+            (None, Some(0.0), -1.0),
+            (Some(0.5), None, 1000.0),
+            (None, None, 0.0),
+        ]);
+        assert!("".parse::<Bucketizer<f64>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_entries() {
+        assert_eq!("0:10=5;10:20".parse::<Bucketizer<f64>>(), Err(ParseError::MissingValue { index: 1 }));
+        assert_eq!("0-10=5".parse::<Bucketizer<f64>>(), Err(ParseError::MissingSeparator { index: 0 }));
+        assert_eq!("0:ten=5".parse::<Bucketizer<f64>>(), Err(ParseError::InvalidNumber { index: 0 }));
+        assert_eq!("0:10=".parse::<Bucketizer<f64>>(), Err(ParseError::InvalidNumber { index: 0 }));
+    }
+
+    #[test]
+    fn to_dsl_round_trips() {
+        let dsl = ":-1.5=-2;-1.5:0=-1;0:0.001=0;0.001:=1000000";
+        let bucketizer: Bucketizer<f64> = dsl.parse().unwrap();
+
+        assert_eq!(bucketizer.to_dsl(), dsl);
+        assert_eq!(bucketizer.to_dsl().parse::<Bucketizer<f64>>(), Ok(bucketizer));
+        assert_eq!(Bucketizer::<f64>::new().to_dsl(), "");
+    }
+}
@@ -0,0 +1,127 @@
+//! Bucketing `Duration`s with bounds written in milliseconds.
+
+use std::time::Duration;
+
+use Bucketizer;
+
+/// A `Bucketizer` for `Duration` inputs whose bucket bounds are given in whole
+/// milliseconds, which suits latency classification.
+///
+/// A `Bucketizer<Duration, V>` works too, but needs every bound written out as a
+/// `Duration`. Lookups compare the full `Duration`, so sub-millisecond inputs are
+/// ordered correctly against the bounds.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use bucketize::DurationBucketizer;
+///
+/// let b = DurationBucketizer::new()
+///     .bucket(None, Some(10), "fast")
+///     .bucket(Some(10), Some(1000), "ok")
+///     .bucket(Some(1000), None, "slow");
+///
+/// assert_eq!(b.bucketize(Duration::from_micros(9_999)), Some("fast"));
+/// assert_eq!(b.bucketize(Duration::from_secs(3)), Some("slow"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DurationBucketizer<V: Clone> {
+    inner: Bucketizer<Duration, V>,
+}
+
+impl<V: Clone> DurationBucketizer<V> {
+    /// Create a new `DurationBucketizer` with no buckets configured.
+    pub fn new() -> Self {
+        DurationBucketizer {
+            inner: Bucketizer::new(),
+        }
+    }
+
+    /// Add a new bucket from `min_ms` up to but not including `max_ms` milliseconds,
+    /// if each is present, like `Bucketizer::bucket()`.
+    pub fn bucket(self, min_ms: Option<u64>, max_ms: Option<u64>, value: V) -> Self {
+        DurationBucketizer {
+            inner: self.inner.bucket(min_ms.map(Duration::from_millis), max_ms.map(Duration::from_millis), value),
+        }
+    }
+
+    /// Add a new bucket from `min_ms` up to and including `max_ms` milliseconds, if
+    /// each is present, like `Bucketizer::bucket_inclusive()`.
+    pub fn bucket_inclusive(self, min_ms: Option<u64>, max_ms: Option<u64>, value: V) -> Self {
+        DurationBucketizer {
+            inner: self.inner.bucket_inclusive(min_ms.map(Duration::from_millis), max_ms.map(Duration::from_millis), value),
+        }
+    }
+
+    /// Get the bucketized value of `input`.
+    pub fn bucketize(&self, input: Duration) -> Option<V> {
+        self.inner.bucketize(input)
+    }
+
+    /// Get the index of the first bucket `input` falls into.
+    pub fn bucketize_index(&self, input: Duration) -> Option<usize> {
+        self.inner.bucketize_index(input)
+    }
+
+    /// The number of buckets configured.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether no buckets have been configured.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Convert into a `Bucketizer` over `Duration`s with the same buckets.
+    pub fn into_inner(self) -> Bucketizer<Duration, V> {
+        self.inner
+    }
+}
+
+impl<V: Clone> Default for DurationBucketizer<V> {
+    /// Equivalent to `DurationBucketizer::new()`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::DurationBucketizer;
+
+    #[test]
+    fn classifies_latencies() {
+        let bucketizer = DurationBucketizer::new()
+            .bucket(None, Some(10), "low")
+            .bucket(Some(10), Some(1000), "medium")
+            .bucket(Some(1000), None, "high");
+
+        assert_eq!(bucketizer.bucketize(Duration::from_millis(5)), Some("low"));
+        assert_eq!(bucketizer.bucketize(Duration::from_millis(50)), Some("medium"));
+        assert_eq!(bucketizer.bucketize(Duration::from_secs(5)), Some("high"));
+        assert_eq!(bucketizer.bucketize(Duration::from_secs(86_400)), Some("high"));
+    }
+
+    #[test]
+    fn millisecond_boundaries() {
+        let bucketizer = DurationBucketizer::new()
+            .bucket(Some(10), Some(20), 1)
+            .bucket_inclusive(Some(20), Some(30), 2);
+
+        assert_eq!(bucketizer.bucketize(Duration::from_micros(9_999)), None);
+        assert_eq!(bucketizer.bucketize(Duration::from_millis(10)), Some(1));
+        assert_eq!(bucketizer.bucketize(Duration::from_micros(19_999)), Some(1));
+        assert_eq!(bucketizer.bucketize(Duration::from_millis(30)), Some(2));
+        assert_eq!(bucketizer.bucketize_index(Duration::from_nanos(30_000_001)), None);
+    }
+
+    #[test]
+    fn into_inner_keeps_buckets() {
+        let inner = DurationBucketizer::new().bucket(Some(1), Some(2), 'a').into_inner();
+
+        assert_eq!(inner.first(), Some(&(Some(Duration::from_millis(1)), Some(Duration::from_millis(2)), 'a')));
+    }
+}
@@ -0,0 +1,110 @@
+//! Errors reported when a bucket configuration or an input value is rejected.
+
+#[cfg(feature = "std")]
+use std::error::Error;
+use std::fmt;
+
+/// A problem with the buckets configured on a `Bucketizer`. Indexes count from
+/// zero in the order buckets were added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketError {
+    /// The bucket's `min` is greater than its `max`, so it can never match.
+    MinExceedsMax { index: usize },
+    /// The bucket starts before the bucket added ahead of it.
+    Unsorted { index: usize },
+    /// The two buckets share some values.
+    Overlapping { first: usize, second: usize },
+    /// `Bucketizer::logarithmic()` was given a bound that isn't greater than zero,
+    /// where the logarithm is undefined.
+    NonPositiveBound,
+    /// The bucket is shadowed by a catch-all bucket with no `min` or `max` that
+    /// takes precedence over it, whether by coming first with the same priority or
+    /// by having a higher one, so it can never match.
+    Unreachable { index: usize },
+    /// The bucket has a bound that can't be compared to anything, like
+    /// `f64::NAN`, so it can never match.
+    IncomparableBound { index: usize },
+    /// `Bucketizer::split()` was given a point that isn't inside the bucket, or
+    /// there is no bucket at that index.
+    SplitOutsideBucket { index: usize },
+}
+
+impl fmt::Display for BucketError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BucketError::MinExceedsMax { index } =>
+                write!(f, "bucket {} has a min greater than its max", index),
+            BucketError::Unsorted { index } =>
+                write!(f, "bucket {} starts before the bucket ahead of it", index),
+            BucketError::Overlapping { first, second } =>
+                write!(f, "buckets {} and {} overlap", first, second),
+            BucketError::NonPositiveBound =>
+                write!(f, "logarithmic bounds must be greater than zero"),
+            BucketError::Unreachable { index } =>
+                write!(f, "bucket {} is shadowed by a catch-all bucket and can never match", index),
+            BucketError::IncomparableBound { index } =>
+                write!(f, "bucket {} has a bound that can't be compared", index),
+            BucketError::SplitOutsideBucket { index } =>
+                write!(f, "bucket {} doesn't contain the split point", index),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BucketError {}
+
+/// A problem with a text bucket definition, as parsed by `Bucketizer::from_str()`
+/// or `Bucketizer::from_csv()`. Indexes count from zero in the order buckets are
+/// listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The bucket has no `=` separating its range from its value.
+    MissingValue { index: usize },
+    /// The bucket's range has no `:` separating its `min` from its `max`.
+    MissingSeparator { index: usize },
+    /// The bucket's `min`, `max` or value isn't a valid number.
+    InvalidNumber { index: usize },
+    /// The CSV row doesn't have exactly three cells, for `min`, `max` and value.
+    WrongCellCount { index: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::MissingValue { index } =>
+                write!(f, "bucket {} has no `=` before its value", index),
+            ParseError::MissingSeparator { index } =>
+                write!(f, "bucket {} has no `:` between its min and max", index),
+            ParseError::InvalidNumber { index } =>
+                write!(f, "bucket {} has a bound or value that isn't a number", index),
+            ParseError::WrongCellCount { index } =>
+                write!(f, "bucket {} doesn't have exactly three cells", index),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseError {}
+
+/// A problem with a value passed to be bucketized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketizeError {
+    /// The value can't be compared to bucket bounds, as with `f64::NAN`.
+    NotComparable,
+    /// The `Bucketizer` has no buckets, so nothing could ever match.
+    NoBuckets,
+}
+
+impl fmt::Display for BucketizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BucketizeError::NotComparable =>
+                write!(f, "value can't be compared to bucket bounds"),
+            BucketizeError::NoBuckets =>
+                write!(f, "no buckets are configured"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BucketizeError {}
@@ -0,0 +1,90 @@
+//! Bucketizing with a fixed number of buckets, without allocating.
+
+use {Bucket, Entry};
+
+/// A bucketizer with exactly `N` buckets stored inline in an array, so building
+/// and using one never allocates.
+///
+/// Buckets are evaluated in array order, min-inclusive and max-exclusive, exactly
+/// like a `Bucketizer` built with `bucket()`. The crate itself still depends on
+/// `alloc` for `Bucketizer`, even without the `std` feature.
+///
+/// # Example
+/// ```
+/// use bucketize::ConstBucketizer;
+///
+/// let b = ConstBucketizer::new([
+///     (None, Some(0.0), -1.0),
+///     (Some(0.0), Some(10.0), 5.0),
+///     (Some(10.0), None, 10.0),
+/// ]);
+///
+/// assert_eq!(b.bucketize(3.0), Some(5.0));
+/// assert_eq!(b.len(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstBucketizer<T: PartialOrd + Clone, const N: usize, V: Clone = T> {
+    buckets: [Entry<T, V>; N],
+}
+
+impl<T: PartialOrd + Clone, const N: usize, V: Clone> ConstBucketizer<T, N, V> {
+    /// Create a `ConstBucketizer` from `(min, max, value)` buckets, evaluated in
+    /// array order.
+    pub fn new(buckets: [Bucket<T, V>; N]) -> Self {
+        ConstBucketizer {
+            buckets: buckets.map(|bucket| Entry::new(bucket, true, false)),
+        }
+    }
+
+    /// Get the bucketized value of `input`, exactly as `Bucketizer::bucketize()`
+    /// would.
+    pub fn bucketize(&self, input: T) -> Option<V> {
+        self.bucketize_index(input).map(|index| self.buckets[index].bucket.2.clone())
+    }
+
+    /// Get the index of the first bucket `input` falls into.
+    pub fn bucketize_index(&self, input: T) -> Option<usize> {
+        self.buckets.iter().position(|entry| entry.contains(&input))
+    }
+
+    /// The number of buckets, which is always `N`.
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    /// Whether there are no buckets, which is the case only when `N` is zero.
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstBucketizer;
+    use Bucketizer;
+
+    #[test]
+    fn matches_vec_bucketizer() {
+        let buckets = [
+            (None, Some(0.0), -1.0),
+            (Some(0.0), Some(10.0), 5.0),
+            (Some(10.0), Some(20.0), 15.0),
+        ];
+        let fixed: ConstBucketizer<f64, 3> = ConstBucketizer::new(buckets);
+        let bucketizer: Bucketizer<f64> = buckets.iter().cloned().collect();
+
+        for &input in &[-5.0, 0.0, 5.0, 10.0, 19.99, 20.0, f64::NAN] {
+            assert_eq!(fixed.bucketize(input), bucketizer.bucketize(input), "{}", input);
+            assert_eq!(fixed.bucketize_index(input), bucketizer.bucketize_index(input));
+        }
+        assert_eq!(fixed.len(), 3);
+    }
+
+    #[test]
+    fn no_buckets() {
+        let fixed = ConstBucketizer::<i32, 0>::new([]);
+
+        assert!(fixed.is_empty());
+        assert_eq!(fixed.bucketize(1), None);
+    }
+}
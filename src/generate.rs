@@ -0,0 +1,495 @@
+//! Generating buckets from parameters or sample data rather than adding each by hand.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::ops::{Add, Div, Sub};
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use BucketError;
+use Bucketizer;
+
+impl Bucketizer<f64> {
+    /// Create a `Bucketizer` with `n` equal-width buckets that together cover
+    /// `[lo, hi)`. Each bucket's value is its lower edge, so bucketizing a value
+    /// rounds it down to the nearest edge.
+    ///
+    /// Edges are computed from `lo` and `hi` directly rather than by repeatedly
+    /// adding the width, so the last bucket ends exactly at `hi` and neighboring
+    /// buckets always meet. If `n` is zero, no buckets are created.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::linear(0.0, 10.0, 5);
+    ///
+    /// assert_eq!(b.len(), 5);
+    /// assert_eq!(b.bucketize(3.0), Some(2.0));
+    /// assert_eq!(b.bucketize(10.0), None);
+    /// ```
+    pub fn linear(lo: f64, hi: f64, n: usize) -> Self {
+        let edge = |i: usize| if i == n {
+            hi
+        } else {
+            lo + (hi - lo) * i as f64 / n as f64
+        };
+        (0..n)
+            .map(|i| (Some(edge(i)), Some(edge(i + 1)), edge(i)))
+            .collect()
+    }
+
+    /// Create a `Bucketizer` with `n` buckets that together cover `[lo, hi)`, with
+    /// edges spaced geometrically so each bucket is the same number of times wider
+    /// than the one before it. Each bucket's value is its lower edge.
+    ///
+    /// This suits values spanning several orders of magnitude, like latencies. The
+    /// first bucket starts exactly at `lo` and the last ends exactly at `hi`. If `n`
+    /// is zero, no buckets are created.
+    ///
+    /// Returns `BucketError::NonPositiveBound` if `lo` or `hi` isn't greater than zero.
+    ///
+    /// This needs the `std` feature, which is enabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::logarithmic(1.0, 1000.0, 3).unwrap();
+    ///
+    /// // Buckets are about [1, 10), [10, 100) and [100, 1000).
+    /// assert_eq!(b.bucketize(5.0), Some(1.0));
+    /// assert_eq!(b.bucketize_index(500.0), Some(2));
+    /// assert!(Bucketizer::logarithmic(0.0, 1000.0, 3).is_err());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn logarithmic(lo: f64, hi: f64, n: usize) -> Result<Self, BucketError> {
+        if !(lo > 0.0 && hi > 0.0) {
+            return Err(BucketError::NonPositiveBound);
+        }
+        let ratio = hi / lo;
+        let edge = |i: usize| match i {
+            0 => lo,
+            i if i == n => hi,
+            i => lo * ratio.powf(i as f64 / n as f64),
+        };
+        Ok((0..n)
+            .map(|i| (Some(edge(i)), Some(edge(i + 1)), edge(i)))
+            .collect())
+    }
+
+    /// Create a `Bucketizer` with up to `n` contiguous buckets that each hold about
+    /// the same number of `samples`, so that with `n = 4` the edges are the quartiles.
+    /// Each bucket's value is its midpoint.
+    ///
+    /// `samples` is sorted in place. The buckets cover every sample from the smallest
+    /// to the largest, so the last bucket includes its `max`. When there are fewer
+    /// distinct samples than buckets, some edges would coincide; those empty buckets
+    /// are left out rather than created. `NaN` samples are ignored, and if there are
+    /// no other samples or `n` is zero, no buckets are created.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut samples = vec![8.0, 1.0, 6.0, 3.0, 2.0, 7.0, 5.0, 4.0];
+    /// let b = Bucketizer::from_quantiles(&mut samples, 4);
+    ///
+    /// // Buckets are [1, 3), [3, 5), [5, 7) and [7, 8].
+    /// assert_eq!(b.bucketize(2.5), Some(2.0));
+    /// assert_eq!(b.bucketize(8.0), Some(7.5));
+    /// ```
+    pub fn from_quantiles(samples: &mut [f64], n: usize) -> Self {
+        samples.sort_by(f64::total_cmp);
+        let start = samples.iter().position(|x| !x.is_nan()).unwrap_or(samples.len());
+        let end = samples.iter().rposition(|x| !x.is_nan()).map_or(start, |i| i + 1);
+        let samples = &samples[start..end];
+        if samples.is_empty() || n == 0 {
+            return Bucketizer::new();
+        }
+
+        let mut edges: Vec<f64> = (0..n).map(|i| samples[i * samples.len() / n]).collect();
+        edges.push(samples[samples.len() - 1]);
+        edges.dedup();
+        if edges.len() == 1 {
+            edges.push(edges[0]);
+        }
+        let last = edges.len() - 2;
+        let mut bucketizer = Bucketizer::with_capacity(edges.len() - 1);
+        for (i, pair) in edges.windows(2).enumerate() {
+            let (lo, hi) = (pair[0], pair[1]);
+            let midpoint = lo + (hi - lo) / 2.0;
+            bucketizer = if i == last {
+                bucketizer.bucket_inclusive(Some(lo), Some(hi), midpoint)
+            } else {
+                bucketizer.bucket(Some(lo), Some(hi), midpoint)
+            };
+        }
+        bucketizer
+    }
+
+    /// Create a `Bucketizer` with `n` equal-width contiguous buckets spanning
+    /// `samples`, from the smallest to the largest. Each bucket's value is its
+    /// midpoint, and the last bucket includes its `max`, so every sample falls
+    /// into a bucket.
+    ///
+    /// Edges are computed like `linear()`. If every sample is the same, a single
+    /// bucket holding just that value is created. `NaN` samples are ignored, and if
+    /// there are no other samples or `n` is zero, no buckets are created.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::auto(&[3.0, 0.0, 10.0, 7.0], 2);
+    ///
+    /// // Buckets are [0, 5) and [5, 10].
+    /// assert_eq!(b.bucketize(3.0), Some(2.5));
+    /// assert_eq!(b.bucketize(10.0), Some(7.5));
+    /// ```
+    pub fn auto(samples: &[f64], n: usize) -> Self {
+        let mut samples = samples.iter().cloned().filter(|x| !x.is_nan());
+        let first = match samples.next() {
+            Some(first) if n > 0 => first,
+            _ => return Bucketizer::new(),
+        };
+        let (lo, hi) = samples.fold((first, first), |(lo, hi), x| (lo.min(x), hi.max(x)));
+        if lo == hi {
+            return Bucketizer::new().bucket_inclusive(Some(lo), Some(hi), lo);
+        }
+
+        let edge = |i: usize| if i == n {
+            hi
+        } else {
+            lo + (hi - lo) * i as f64 / n as f64
+        };
+        let mut bucketizer = Bucketizer::with_capacity(n);
+        for i in 0..n {
+            let (lo, hi) = (edge(i), edge(i + 1));
+            let midpoint = lo + (hi - lo) / 2.0;
+            bucketizer = if i == n - 1 {
+                bucketizer.bucket_inclusive(Some(lo), Some(hi), midpoint)
+            } else {
+                bucketizer.bucket(Some(lo), Some(hi), midpoint)
+            };
+        }
+        bucketizer
+    }
+}
+
+impl<T> Bucketizer<T>
+    where T: PartialOrd + Clone + Add<Output = T> + Sub<Output = T> + Div<Output = T> + From<bool> + TryFrom<usize>
+{
+    /// Create a `Bucketizer` with `n` contiguous integer buckets that together cover
+    /// `[lo, hi)`, like `linear()` does for `f64`. Each bucket's value is its lower
+    /// edge.
+    ///
+    /// Every bucket is `(hi - lo) / n` wide, rounded down, except the last, which
+    /// also takes the remainder so it ends exactly at `hi`. If there are fewer than
+    /// `n` values in `[lo, hi)`, each gets a bucket of its own. If `n` is zero or
+    /// `hi` isn't greater than `lo`, no buckets are created. `hi - lo` must fit in
+    /// `T`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::linear_int(0u32, 10, 3);
+    ///
+    /// // Buckets are [0, 3), [3, 6) and [6, 10).
+    /// assert_eq!(b.bucketize(4), Some(3));
+    /// assert_eq!(b.bucketize(9), Some(6));
+    /// assert_eq!(b.bucketize(10), None);
+    /// ```
+    pub fn linear_int(lo: T, hi: T, n: usize) -> Self {
+        if n == 0 || lo.partial_cmp(&hi) != Some(Ordering::Less) {
+            return Bucketizer::new();
+        }
+        let (zero, one) = (T::from(false), T::from(true));
+        let width = match T::try_from(n) {
+            Ok(count) => (hi.clone() - lo.clone()) / count,
+            Err(_) => zero.clone(),
+        };
+        let width = if width > zero { width } else { one };
+
+        let mut bucketizer = Bucketizer::with_capacity(n);
+        let mut edge = lo;
+        for i in 0..n {
+            let next = if i == n - 1 || hi.clone() - edge.clone() <= width {
+                hi.clone()
+            } else {
+                edge.clone() + width.clone()
+            };
+            let last = next == hi;
+            bucketizer.add_bucket(Some(edge.clone()), Some(next.clone()), edge);
+            if last {
+                break;
+            }
+            edge = next;
+        }
+        bucketizer
+    }
+
+    /// Create a `Bucketizer` with `n` contiguous integer buckets spanning `samples`,
+    /// from the smallest to the largest, like `auto()` does for `f64`. Each bucket's
+    /// value is its midpoint, rounded down, and the last bucket includes its `max`,
+    /// so every sample falls into a bucket.
+    ///
+    /// Edges are computed like `linear_int()`. If every sample is the same, a single
+    /// bucket holding just that value is created, and if there are no samples or `n`
+    /// is zero, no buckets are created.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::auto_int(&[3, 17, 9, 12], 2);
+    ///
+    /// // Buckets are [3, 10) and [10, 17].
+    /// assert_eq!(b.bucketize(5), Some(6));
+    /// assert_eq!(b.bucketize(17), Some(13));
+    /// ```
+    pub fn auto_int(samples: &[T], n: usize) -> Self {
+        let mut samples = samples.iter().cloned();
+        let first = match samples.next() {
+            Some(first) if n > 0 => first,
+            _ => return Bucketizer::new(),
+        };
+        let (lo, hi) = samples.fold((first.clone(), first), |(lo, hi), x| {
+            let lo = if x < lo { x.clone() } else { lo };
+            let hi = if x > hi { x } else { hi };
+            (lo, hi)
+        });
+        if lo == hi {
+            return Bucketizer::new().bucket_inclusive(Some(lo.clone()), Some(hi), lo);
+        }
+
+        let two = T::from(true) + T::from(true);
+        let mut bucketizer = Bucketizer::linear_int(lo, hi, n);
+        for entry in &mut bucketizer.buckets {
+            if let (Some(ref min), Some(ref max), ref mut value) = entry.bucket {
+                *value = min.clone() + (max.clone() - min.clone()) / two.clone();
+            }
+        }
+        if let Some(last) = bucketizer.buckets.last_mut() {
+            last.max_inclusive = true;
+        }
+        bucketizer
+    }
+}
+
+impl Bucketizer<u16> {
+    /// Create a `Bucketizer` for HTTP status codes, with a bucket for each of the
+    /// `1xx` to `5xx` classes. Each bucket's value is the first code in its class,
+    /// so `404` produces `400`. Codes outside `100..600` match nothing.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let classes = Bucketizer::<u16>::status_classes()
+    ///     .map_values(|class| match class {
+    ///         200 => "success",
+    ///         400 | 500 => "error",
+    ///         _ => "other",
+    ///     });
+    ///
+    /// assert_eq!(classes.bucketize(204), Some("success"));
+    /// assert_eq!(classes.bucketize(503), Some("error"));
+    /// ```
+    pub fn status_classes() -> Self {
+        (1..6)
+            .map(|class| (Some(class * 100), Some(class * 100 + 100), class * 100))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    use BucketError;
+    use Bucketizer;
+
+    #[test]
+    fn linear_buckets_are_contiguous() {
+        let bucketizer = Bucketizer::linear(0.0, 10.0, 5);
+
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), vec![
+            (Some(0.0), Some(2.0), 0.0),
+            (Some(2.0), Some(4.0), 2.0),
+            (Some(4.0), Some(6.0), 4.0),
+            (Some(6.0), Some(8.0), 6.0),
+            (Some(8.0), Some(10.0), 8.0),
+        ]);
+        assert_eq!(bucketizer.gaps(), vec![(None, Some(0.0)), (Some(10.0), None)]);
+    }
+
+    #[test]
+    fn linear_boundaries() {
+        let bucketizer = Bucketizer::linear(0.0, 10.0, 5);
+
+        assert_eq!(bucketizer.bucketize(0.0), Some(0.0));
+        assert_eq!(bucketizer.bucketize(1.999), Some(0.0));
+        assert_eq!(bucketizer.bucketize(2.0), Some(2.0));
+        assert_eq!(bucketizer.bucketize(9.999), Some(8.0));
+        assert_eq!(bucketizer.bucketize(10.0), None);
+        assert_eq!(bucketizer.bucketize(-0.001), None);
+    }
+
+    #[test]
+    fn linear_uneven_width_ends_at_hi() {
+        let bucketizer = Bucketizer::linear(0.0, 1.0, 3);
+
+        assert_eq!(bucketizer.len(), 3);
+        assert_eq!(bucketizer.iter().next_back(), Some((Some(2.0 / 3.0), Some(1.0), 2.0 / 3.0)));
+        assert_eq!(bucketizer.gaps().len(), 2);
+    }
+
+    #[test]
+    fn linear_zero_buckets() {
+        assert!(Bucketizer::linear(0.0, 10.0, 0).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn logarithmic_edges_are_geometric() {
+        let bucketizer = Bucketizer::logarithmic(1.0, 1000.0, 3).unwrap();
+        let edges: Vec<f64> = bucketizer.iter().map(|(min, _, _)| min.unwrap()).collect();
+
+        assert_eq!(bucketizer.len(), 3);
+        assert_eq!(edges[0], 1.0);
+        assert!((edges[1] - 10.0).abs() < 1e-9);
+        assert!((edges[2] - 100.0).abs() < 1e-9);
+        assert_eq!(bucketizer.iter().next_back().unwrap().1, Some(1000.0));
+        assert_eq!(bucketizer.gaps().len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn logarithmic_lookup() {
+        let bucketizer = Bucketizer::logarithmic(1.0, 1000.0, 3).unwrap();
+
+        assert_eq!(bucketizer.bucketize_index(50.0), Some(1));
+        assert!((bucketizer.bucketize(50.0).unwrap() - 10.0).abs() < 1e-9);
+        assert_eq!(bucketizer.bucketize(1.0), Some(1.0));
+        assert_eq!(bucketizer.bucketize(1000.0), None);
+        assert_eq!(bucketizer.bucketize(0.5), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn logarithmic_rejects_non_positive_bounds() {
+        assert_eq!(Bucketizer::logarithmic(0.0, 10.0, 2), Err(BucketError::NonPositiveBound));
+        assert_eq!(Bucketizer::logarithmic(-1.0, 10.0, 2), Err(BucketError::NonPositiveBound));
+        assert_eq!(Bucketizer::logarithmic(1.0, -10.0, 2), Err(BucketError::NonPositiveBound));
+        assert_eq!(Bucketizer::logarithmic(f64::NAN, 10.0, 2), Err(BucketError::NonPositiveBound));
+    }
+
+    #[test]
+    fn quantile_edges() {
+        let mut samples: Vec<f64> = (1..=12).rev().map(f64::from).collect();
+        let bucketizer = Bucketizer::from_quantiles(&mut samples, 4);
+
+        assert_eq!(samples[0], 1.0);
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), vec![
+            (Some(1.0), Some(4.0), 2.5),
+            (Some(4.0), Some(7.0), 5.5),
+            (Some(7.0), Some(10.0), 8.5),
+            (Some(10.0), Some(12.0), 11.0),
+        ]);
+        assert_eq!(bucketizer.bucketize(12.0), Some(11.0));
+        assert_eq!(bucketizer.bucketize(12.5), None);
+        assert_eq!(bucketizer.gaps(), vec![(None, Some(1.0)), (Some(12.0), None)]);
+    }
+
+    #[test]
+    fn quantiles_with_fewer_samples_than_buckets() {
+        let bucketizer = Bucketizer::from_quantiles(&mut [3.0, 1.0], 4);
+
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), vec![(Some(1.0), Some(3.0), 2.0)]);
+        assert_eq!(bucketizer.bucketize(3.0), Some(2.0));
+
+        let single = Bucketizer::from_quantiles(&mut [5.0, 5.0, f64::NAN], 3);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single.bucketize(5.0), Some(5.0));
+    }
+
+    #[test]
+    fn quantiles_of_nothing() {
+        assert!(Bucketizer::from_quantiles(&mut [], 4).is_empty());
+        assert!(Bucketizer::from_quantiles(&mut [f64::NAN], 4).is_empty());
+        assert!(Bucketizer::from_quantiles(&mut [1.0, 2.0], 0).is_empty());
+    }
+
+    #[test]
+    fn status_classes() {
+        let bucketizer = Bucketizer::<u16>::status_classes();
+
+        assert_eq!(bucketizer.len(), 5);
+        assert_eq!(bucketizer.bucketize(200), Some(200));
+        assert_eq!(bucketizer.bucketize(404), Some(400));
+        assert_eq!(bucketizer.bucketize(503), Some(500));
+        assert_eq!(bucketizer.bucketize(99), None);
+        assert_eq!(bucketizer.bucketize(600), None);
+    }
+
+    #[test]
+    fn status_classes_nearest() {
+        let bucketizer = Bucketizer::<u16>::status_classes();
+
+        assert_eq!(bucketizer.bucketize_nearest(0), Some(100));
+        assert_eq!(bucketizer.bucketize_nearest(u16::MAX), Some(500));
+        assert_eq!(bucketizer.bucketize_saturating(700), Some(500));
+    }
+
+    #[test]
+    fn auto_spans_samples() {
+        let bucketizer = Bucketizer::auto(&[12.0, 2.0, 7.0, f64::NAN, 4.0, 10.0], 4);
+
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), vec![
+            (Some(2.0), Some(4.5), 3.25),
+            (Some(4.5), Some(7.0), 5.75),
+            (Some(7.0), Some(9.5), 8.25),
+            (Some(9.5), Some(12.0), 10.75),
+        ]);
+        assert_eq!(bucketizer.bucketize(12.0), Some(10.75));
+        assert_eq!(bucketizer.bucketize(2.0), Some(3.25));
+        assert_eq!(bucketizer.bucketize(12.5), None);
+    }
+
+    #[test]
+    fn auto_degenerate_samples() {
+        assert!(Bucketizer::auto(&[], 4).is_empty());
+        assert!(Bucketizer::auto(&[f64::NAN], 4).is_empty());
+        assert!(Bucketizer::auto(&[1.0, 2.0], 0).is_empty());
+
+        let single = Bucketizer::auto(&[5.0, 5.0], 3);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single.bucketize(5.0), Some(5.0));
+    }
+
+    #[test]
+    fn linear_int_edges() {
+        fn edges<T: PartialOrd + Clone>(b: &Bucketizer<T>) -> Vec<(T, T)> {
+            b.iter().map(|(min, max, _)| (min.unwrap(), max.unwrap())).collect()
+        }
+
+        assert_eq!(edges(&Bucketizer::linear_int(0, 10, 3)), vec![(0, 3), (3, 6), (6, 10)]);
+        assert_eq!(edges(&Bucketizer::linear_int(0u8, 255, 5)),
+                   vec![(0, 51), (51, 102), (102, 153), (153, 204), (204, 255)]);
+        assert_eq!(edges(&Bucketizer::linear_int(-60i8, 60, 4)), vec![(-60, -30), (-30, 0), (0, 30), (30, 60)]);
+        assert_eq!(edges(&Bucketizer::linear_int(0u8, 3, 300)), vec![(0, 1), (1, 2), (2, 3)]);
+        assert!(Bucketizer::linear_int(5, 5, 3).is_empty());
+        assert!(Bucketizer::linear_int(0, 10, 0).is_empty());
+    }
+
+    #[test]
+    fn auto_int_spans_samples() {
+        let bucketizer = Bucketizer::auto_int(&[250u8, 10, 130, 70], 4);
+
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), vec![
+            (Some(10), Some(70), 40),
+            (Some(70), Some(130), 100),
+            (Some(130), Some(190), 160),
+            (Some(190), Some(250), 220),
+        ]);
+        assert_eq!(bucketizer.bucketize(250), Some(220));
+        assert_eq!(bucketizer.bucketize(251), None);
+        assert_eq!(Bucketizer::auto_int(&[7, 7], 3).bucketize(7), Some(7));
+        assert!(Bucketizer::<i32>::auto_int(&[], 3).is_empty());
+    }
+}
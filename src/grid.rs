@@ -0,0 +1,117 @@
+//! Bucketing `(x, y)` pairs into the cells of a grid.
+
+use alloc::vec::Vec;
+
+use Bucketizer;
+
+/// Buckets `(x, y)` pairs into the cells of a grid, with one list of `(min, max)`
+/// buckets along each axis and a value for every cell.
+///
+/// Each axis is evaluated like a `Bucketizer`, in order and min-inclusive,
+/// max-exclusive, and a pair falls into the cell where its `x` and `y` buckets
+/// cross.
+///
+/// # Example
+/// ```
+/// use bucketize::Bucketizer2D;
+///
+/// let b = Bucketizer2D::new(
+///     vec![(Some(0.0), Some(10.0)), (Some(10.0), Some(20.0))],
+///     vec![(Some(0.0), Some(5.0)), (Some(5.0), None)],
+///     vec![
+///         vec!["bottom left", "bottom right"],
+///         vec!["top left", "top right"],
+///     ],
+/// );
+///
+/// assert_eq!(b.bucketize(15.0, 2.0), Some("bottom right"));
+/// assert_eq!(b.bucketize(3.0, 100.0), Some("top left"));
+/// assert_eq!(b.bucketize(25.0, 2.0), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucketizer2D<T: PartialOrd + Clone, V: Clone> {
+    x_axis: Bucketizer<T, ()>,
+    y_axis: Bucketizer<T, ()>,
+    cells: Vec<Vec<V>>,
+}
+
+impl<T: PartialOrd + Clone, V: Clone> Bucketizer2D<T, V> {
+    /// Create a grid from the `(min, max)` buckets along each axis, where open bounds
+    /// are `None`, and the cell values as rows, so that `cells[row][column]` is the
+    /// value for the `row`th `y` bucket and the `column`th `x` bucket.
+    ///
+    /// # Panics
+    /// Panics if there isn't exactly one row for each `y` bucket, with exactly one
+    /// value for each `x` bucket.
+    pub fn new(x_axis: Vec<(Option<T>, Option<T>)>, y_axis: Vec<(Option<T>, Option<T>)>, cells: Vec<Vec<V>>) -> Self {
+        assert_eq!(cells.len(), y_axis.len(), "expected one row of cells for each y bucket");
+        for row in &cells {
+            assert_eq!(row.len(), x_axis.len(), "expected one cell in each row for each x bucket");
+        }
+        Bucketizer2D {
+            x_axis: x_axis.into_iter().map(|(min, max)| (min, max, ())).collect(),
+            y_axis: y_axis.into_iter().map(|(min, max)| (min, max, ())).collect(),
+            cells,
+        }
+    }
+
+    /// Get the value of the cell `(x, y)` falls into, or `None` if `x` or `y` falls
+    /// into no bucket on its axis.
+    pub fn bucketize(&self, x: T, y: T) -> Option<V> {
+        self.bucketize_index(x, y).map(|(row, column)| self.cells[row][column].clone())
+    }
+
+    /// Get the `(row, column)` index of the cell `(x, y)` falls into, where `row`
+    /// counts `y` buckets and `column` counts `x` buckets.
+    pub fn bucketize_index(&self, x: T, y: T) -> Option<(usize, usize)> {
+        let column = self.x_axis.bucketize_index(x)?;
+        let row = self.y_axis.bucketize_index(y)?;
+        Some((row, column))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bucketizer2D;
+
+    fn grid() -> Bucketizer2D<i32, &'static str> {
+        Bucketizer2D::new(
+            vec![(Some(0), Some(10)), (Some(10), Some(20)), (Some(20), Some(30))],
+            vec![(Some(0), Some(10)), (Some(10), Some(20))],
+            vec![
+                vec!["a", "b", "c"],
+                vec!["d", "e", "f"],
+            ],
+        )
+    }
+
+    #[test]
+    fn classifies_points_into_cells() {
+        let bucketizer = grid();
+
+        assert_eq!(bucketizer.bucketize(5, 5), Some("a"));
+        assert_eq!(bucketizer.bucketize(25, 5), Some("c"));
+        assert_eq!(bucketizer.bucketize(10, 10), Some("e"));
+        assert_eq!(bucketizer.bucketize(29, 19), Some("f"));
+        assert_eq!(bucketizer.bucketize_index(15, 15), Some((1, 1)));
+    }
+
+    #[test]
+    fn misses_outside_grid() {
+        let bucketizer = grid();
+
+        assert_eq!(bucketizer.bucketize(-1, 5), None);
+        assert_eq!(bucketizer.bucketize(5, 20), None);
+        assert_eq!(bucketizer.bucketize(30, 30), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_ragged_cells() {
+        Bucketizer2D::new(
+            vec![(Some(0), Some(10)), (Some(10), Some(20))],
+            vec![(None, None)],
+            vec![vec![1]],
+        );
+    }
+}
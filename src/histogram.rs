@@ -0,0 +1,283 @@
+//! Counting how many values land in each bucket, or how much they weigh.
+
+use alloc::vec::Vec;
+
+use Bucketizer;
+
+/// How many values landed in each bucket of a `Bucketizer`, created by
+/// `Bucketizer::histogram()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// The number of values in each bucket, indexed in the order buckets were added.
+    pub counts: Vec<usize>,
+    /// The number of values that matched no bucket.
+    pub unmatched: usize,
+}
+
+/// The total weight of the values that landed in each bucket of a `Bucketizer`,
+/// created by `Bucketizer::weighted_histogram()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedHistogram {
+    /// The summed weight in each bucket, indexed in the order buckets were added.
+    pub sums: Vec<f64>,
+    /// The summed weight of the values that matched no bucket.
+    pub unmatched: f64,
+}
+
+impl<T: PartialOrd + Clone, V: Clone> Bucketizer<T, V> {
+    /// Count how many of `inputs` fall into each bucket.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(10.0), Some(20.0), 15.0);
+    ///
+    /// let histogram = b.histogram(vec![1.0, 2.0, 12.0, 25.0]);
+    /// assert_eq!(histogram.counts, vec![2, 1]);
+    /// assert_eq!(histogram.unmatched, 1);
+    /// ```
+    pub fn histogram<I: IntoIterator<Item = T>>(&self, inputs: I) -> Histogram {
+        let mut histogram = Histogram {
+            counts: vec![0; self.len()],
+            unmatched: 0,
+        };
+        for input in inputs {
+            match self.bucketize_index(input) {
+                Some(index) => histogram.counts[index] += 1,
+                None => histogram.unmatched += 1,
+            }
+        }
+        histogram
+    }
+
+    /// Count how many of `inputs` fall into each bucket, counting values below or
+    /// above every bucket towards the outermost bucket on that side, as
+    /// `bucketize_saturating()` would.
+    ///
+    /// Only values that fall into a gap between buckets, or any value if there are
+    /// no buckets, are counted as unmatched.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(10.0), Some(20.0), 15.0);
+    ///
+    /// let histogram = b.histogram_saturating(vec![-5.0, 2.0, 12.0, 25.0]);
+    /// assert_eq!(histogram.counts, vec![2, 2]);
+    /// assert_eq!(histogram.unmatched, 0);
+    /// ```
+    pub fn histogram_saturating<I: IntoIterator<Item = T>>(&self, inputs: I) -> Histogram {
+        let mut histogram = Histogram {
+            counts: vec![0; self.len()],
+            unmatched: 0,
+        };
+        for input in inputs {
+            match self.saturating_index(input) {
+                Some(index) => histogram.counts[index] += 1,
+                None => histogram.unmatched += 1,
+            }
+        }
+        histogram
+    }
+
+    /// Find the index of the bucket the most of `inputs` fall into, or `None` if
+    /// none of them fall into any bucket. When several buckets are tied for the most,
+    /// the lowest index wins.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(10.0), Some(20.0), 15.0);
+    ///
+    /// assert_eq!(b.mode(vec![1.0, 12.0, 15.0]), Some(1));
+    /// assert_eq!(b.mode(vec![50.0]), None);
+    /// ```
+    pub fn mode<I: IntoIterator<Item = T>>(&self, inputs: I) -> Option<usize> {
+        let mut mode = None;
+        let mut most = 0;
+        for (index, &count) in self.histogram(inputs).counts.iter().enumerate() {
+            if count > most {
+                mode = Some(index);
+                most = count;
+            }
+        }
+        mode
+    }
+
+    /// Sum the weight of each of `inputs`, given as `(value, weight)` pairs, into
+    /// the bucket its value falls into.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0), Some(1024), "small")
+    ///     .bucket(Some(1024), None, "large");
+    ///
+    /// let bytes = b.weighted_histogram(vec![(100, 100.0), (200, 200.0), (4096, 4096.0)]);
+    /// assert_eq!(bytes.sums, vec![300.0, 4096.0]);
+    /// assert_eq!(bytes.unmatched, 0.0);
+    /// ```
+    pub fn weighted_histogram<I: IntoIterator<Item = (T, f64)>>(&self, inputs: I) -> WeightedHistogram {
+        let mut histogram = WeightedHistogram {
+            sums: vec![0.0; self.len()],
+            unmatched: 0.0,
+        };
+        for (input, weight) in inputs {
+            match self.bucketize_index(input) {
+                Some(index) => histogram.sums[index] += weight,
+                None => histogram.unmatched += weight,
+            }
+        }
+        histogram
+    }
+}
+
+/// Running counts of how many values landed in each bucket of a `Bucketizer`,
+/// for recording values one at a time without keeping them around.
+///
+/// # Example
+/// ```
+/// # use bucketize::{BucketCounter, Bucketizer};
+/// let mut counter = BucketCounter::new(Bucketizer::new()
+///     .bucket(Some(0.0), Some(10.0), 5.0)
+///     .bucket(Some(10.0), Some(20.0), 15.0));
+///
+/// counter.record(3.0);
+/// counter.record(12.0);
+/// counter.record(25.0);
+///
+/// assert_eq!(counter.counts(), &[1, 1]);
+/// assert_eq!(counter.unmatched(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BucketCounter<T: PartialOrd + Clone, V: Clone = T> {
+    bucketizer: Bucketizer<T, V>,
+    histogram: Histogram,
+}
+
+impl<T: PartialOrd + Clone, V: Clone> BucketCounter<T, V> {
+    /// Create a counter for the buckets of `bucketizer`, with nothing recorded yet.
+    pub fn new(bucketizer: Bucketizer<T, V>) -> Self {
+        let histogram = Histogram {
+            counts: vec![0; bucketizer.len()],
+            unmatched: 0,
+        };
+        BucketCounter { bucketizer, histogram }
+    }
+
+    /// Count `input` towards the bucket it falls into.
+    pub fn record(&mut self, input: T) {
+        match self.bucketizer.bucketize_index(input) {
+            Some(index) => self.histogram.counts[index] += 1,
+            None => self.histogram.unmatched += 1,
+        }
+    }
+
+    /// The number of values recorded in each bucket, indexed in the order buckets
+    /// were added.
+    pub fn counts(&self) -> &[usize] {
+        &self.histogram.counts
+    }
+
+    /// The number of values recorded that matched no bucket.
+    pub fn unmatched(&self) -> usize {
+        self.histogram.unmatched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Bucketizer;
+
+    #[test]
+    fn counts_per_bucket() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), "negative")
+            .bucket(Some(0.0), Some(10.0), "small")
+            .bucket(Some(10.0), Some(100.0), "large");
+
+        let histogram = bucketizer.histogram(vec![-5.0, 0.0, 3.0, 9.99, 10.0, 50.0, 99.0, 100.0, 1e6]);
+
+        assert_eq!(histogram.counts, vec![1, 3, 3]);
+        assert_eq!(histogram.unmatched, 2);
+    }
+
+    #[test]
+    fn empty_inputs() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), Some(20), 15);
+
+        let histogram = bucketizer.histogram(Vec::new());
+
+        assert_eq!(histogram.counts, vec![0, 0]);
+        assert_eq!(histogram.unmatched, 0);
+    }
+
+    #[test]
+    fn weights_per_bucket() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), "small")
+            .bucket(Some(10), Some(100), "large");
+
+        let histogram = bucketizer.weighted_histogram(vec![
+            (1, 0.5), (5, 1.5), (10, 2.0), (50, 4.0), (-1, 8.0), (100, 16.0),
+        ]);
+
+        assert_eq!(histogram.sums, vec![2.0, 6.0]);
+        assert_eq!(histogram.unmatched, 24.0);
+    }
+
+    #[test]
+    fn counter_matches_histogram() {
+        use super::BucketCounter;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0), -1)
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), Some(100), 50);
+        let inputs = vec![-5, 0, 3, 9, 10, 50, 99, 100, 1000, 7];
+        let mut counter = BucketCounter::new(bucketizer.clone());
+        for &input in &inputs {
+            counter.record(input);
+        }
+
+        let histogram = bucketizer.histogram(inputs);
+        assert_eq!(counter.counts(), &histogram.counts[..]);
+        assert_eq!(counter.unmatched(), histogram.unmatched);
+    }
+
+    #[test]
+    fn saturating_counts_extremes() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(20), "middle")
+            .bucket(Some(20), Some(30), "high")
+            .bucket(Some(0), Some(10), "low");
+
+        let histogram = bucketizer.histogram_saturating(vec![-100, -1, 5, 15, 25, 30, 1000]);
+
+        assert_eq!(histogram.counts, vec![1, 3, 3]);
+        assert_eq!(histogram.unmatched, 0);
+        assert_eq!(Bucketizer::<i32>::new().histogram_saturating(vec![1, 2]).unmatched, 2);
+    }
+
+    #[test]
+    fn mode_of_skewed_data() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(20), Some(30), 25);
+
+        assert_eq!(bucketizer.mode(vec![1, 21, 22, 23, 15, 29, -5, -6, -7, -8]), Some(2));
+        assert_eq!(bucketizer.mode(vec![12, 2, 21, 13, 3]), Some(0));
+        assert_eq!(bucketizer.mode(vec![100, -100]), None);
+        assert_eq!(bucketizer.mode(Vec::new()), None);
+    }
+}
@@ -14,6 +14,119 @@
 //! assert_eq!(b.bucketize(12.34), Some(15.0));
 //! assert_eq!(b.bucketize(9999.99), None);
 //! ```
+//!
+//! # Output values
+//! The value a bucket produces doesn't have to be the same type as the input being
+//! bucketized. `Bucketizer<T, V>` slots `T` inputs into buckets producing any
+//! `V: Clone`, such as a `&str` label or an enum.
+//!
+//! ```
+//! use bucketize::Bucketizer;
+//!
+//! let b = Bucketizer::new()
+//!     .bucket(None, Some(0.0), "freezing")
+//!     .bucket(Some(0.0), Some(25.0), "mild")
+//!     .bucket(Some(25.0), None, "hot");
+//!
+//! assert_eq!(b.bucketize(12.5), Some("mild"));
+//! ```
+//!
+//! `V` defaults to `T`, so code written against `Bucketizer<T>`, where buckets
+//! produce the same type they take in, keeps working unchanged. The one thing to
+//! watch for when upgrading is type inference: a `Bucketizer` that never has a
+//! bucket added can't infer `V`, so its types need spelling out, as in
+//! `Bucketizer::<f64>::new()`.
+//!
+//! # Features
+//! - `std` (enabled by default): implements `std::error::Error` for the error types
+//!   and enables `Bucketizer::logarithmic()` and `Bucketizer::remap_values()`.
+//!   Without it, the crate is `no_std` and only needs `alloc`.
+//! - `rayon`: adds `Bucketizer::par_bucketize_slice()`, which bucketizes a slice
+//!   in parallel. Implies `std`.
+//! - `serde`: implements `Serialize` and `Deserialize` for `Bucketizer`. A
+//!   `Bucketizer` is represented as a list of `{"min", "max", "value"}` objects in
+//!   evaluation order, with open bounds as `null` and a `"priority"` for buckets
+//!   added with a priority other than 0. A fallback set with
+//!   `default_value()` and labels from `labeled_bucket()` aren't included.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[macro_use]
+extern crate alloc;
+#[cfg(not(any(feature = "std", test)))]
+extern crate core as std;
+
+/// Build a `Bucketizer` from a list of `range => value` buckets, evaluated in the
+/// order they are listed.
+///
+/// Each range can be any of Rust's range types, as accepted by
+/// `Bucketizer::bucket_range()`, so `..` makes a catch-all bucket.
+///
+/// # Example
+/// ```
+/// #[macro_use]
+/// extern crate bucketize;
+///
+/// # fn main() {
+/// let b = bucketize![
+///     0.0..10.0 => 5.0,
+///     10.0..20.0 => 15.0,
+///     .. => 0.0,
+/// ];
+///
+/// assert_eq!(b.bucketize(12.34), Some(15.0));
+/// assert_eq!(b.bucketize(9999.99), Some(0.0));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! bucketize {
+    ($($range:expr => $value:expr),* $(,)?) => {
+        $crate::Bucketizer::new()
+            $(.bucket_range($range, $value))*
+    };
+}
+
+mod analysis;
+mod builder;
+mod comparator;
+mod csv;
+mod dsl;
+mod duration;
+mod error;
+mod fixed;
+mod generate;
+mod grid;
+mod histogram;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod sorted;
+
+pub use builder::BucketizerBuilder;
+pub use comparator::ComparatorBucketizer;
+pub use duration::DurationBucketizer;
+pub use error::{BucketError, BucketizeError, ParseError};
+pub use fixed::ConstBucketizer;
+pub use grid::Bucketizer2D;
+pub use histogram::{BucketCounter, Histogram, WeightedHistogram};
+pub use sorted::SortedBucketizer;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Bound, Div, Index, Mul, Range, RangeBounds, RangeInclusive, Sub};
+use std::slice;
+
+use alloc::vec::Vec;
 
 /// A `Bucketizer` holds the list of buckets you want to slot values into, and does
 /// the bucketization operation.
@@ -23,8 +136,8 @@
 /// a bucket from 0 to 100 and then add a bucket from 2 to 50, nothing will ever
 /// get put in that second bucket.
 ///
-/// Buckets are min-inclusive and max-exclusive. If a given value matches no bucket,
-/// `bucketize` returns `None`.
+/// Buckets are min-inclusive and max-exclusive, unless added with `bucket_inclusive()`
+/// or `bucket_range()`. If a given value matches no bucket, `bucketize` returns `None`.
 ///
 /// # Example
 /// ```
@@ -38,18 +151,89 @@
 /// assert_eq!(b.bucketize(12.34), Some(15.0));
 /// assert_eq!(b.bucketize(9999.99), None);
 /// ```
+///
+/// A `Bucketizer` is `Eq` and `Hash` when its input and output types are, so one
+/// over integers can be used as a `HashMap` key. One over `f64` can't, because
+/// `f64` is neither.
+#[derive(Debug, Clone)]
+pub struct Bucketizer<T: PartialOrd + Clone, V: Clone = T> {
+    buckets: Vec<Entry<T, V>>,
+    default: Option<V>,
+    /// At least the highest priority of any bucket, so `bucketize()` can stop at
+    /// the first match with this priority. Only raised as buckets are added.
+    max_priority: i32,
+}
+
+/// A single bucket as `(min, max, value)`, the same shape passed to `bucket()`.
+pub type Bucket<T, V = T> = (Option<T>, Option<T>, V);
+
+/// A bucket's bounds, whether each is inclusive, and its value, as returned by
+/// `Bucketizer::as_ranges()`.
 #[derive(Debug, Clone, PartialEq)]
-pub struct Bucketizer<T: PartialOrd + Copy> {
-    buckets: Vec<Bucket<T>>,
+pub struct RangeSpec<T, V = T> {
+    /// The bucket's `min`, or `None` if it is open.
+    pub lower: Option<T>,
+    /// The bucket's `max`, or `None` if it is open.
+    pub upper: Option<T>,
+    /// Whether a value equal to `lower` falls into the bucket.
+    pub inclusive_lower: bool,
+    /// Whether a value equal to `upper` falls into the bucket.
+    pub inclusive_upper: bool,
+    /// The value the bucket produces.
+    pub value: V,
 }
 
-type Bucket<T> = (Option<T>, Option<T>, T);
+/// A configured bucket, along with how its `min` and `max` are compared.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Entry<T, V> {
+    bucket: Bucket<T, V>,
+    min_inclusive: bool,
+    max_inclusive: bool,
+    label: Option<&'static str>,
+    priority: i32,
+}
 
-impl<T: PartialOrd + Copy> Bucketizer<T> {
+impl<T: PartialOrd + Clone, V: Clone> Bucketizer<T, V> {
     /// Create a new `Bucketizer` with no buckets configured.
     pub fn new() -> Self {
         Bucketizer {
-            buckets: Vec::<Bucket<T>>::new()
+            buckets: Vec::<Entry<T, V>>::new(),
+            default: None,
+            max_priority: 0,
+        }
+    }
+
+    /// Create a new `Bucketizer` with no buckets configured, with room for at least
+    /// `capacity` buckets before it needs to reallocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Bucketizer {
+            buckets: Vec::with_capacity(capacity),
+            default: None,
+            max_priority: 0,
+        }
+    }
+
+    /// Create a `Bucketizer` from `(min, max, value)` tuples already collected into a
+    /// `Vec`, as if each were passed to `bucket()` in order. The buckets are stored in
+    /// a single allocation of exactly the right size.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::with_buckets(vec![
+    ///     (Some(0.0), Some(10.0), 5.0),
+    ///     (Some(10.0), None, 15.0),
+    /// ]);
+    ///
+    /// assert_eq!(b.bucketize(12.34), Some(15.0));
+    /// ```
+    pub fn with_buckets(buckets: Vec<Bucket<T, V>>) -> Self {
+        Bucketizer {
+            buckets: buckets.into_iter()
+                .map(|bucket| Entry::new(bucket, true, false))
+                .collect(),
+            default: None,
+            max_priority: 0,
         }
     }
 
@@ -86,78 +270,3126 @@ impl<T: PartialOrd + Copy> Bucketizer<T> {
     /// assert_eq!(b.bucketize(12.0), Some(10.0));
     /// assert_eq!(b.bucketize(-10.0), None);
     /// ```
-    pub fn bucket(self, min: Option<T>, max: Option<T>, value: T) -> Self {
+    pub fn bucket(self, min: Option<T>, max: Option<T>, value: V) -> Self {
         let mut new = self;
-        new.buckets.push((min, max, value));
+        new.add_bucket(min, max, value);
         new
     }
 
-    /// Get the bucketized value of `input` based on the previously configured `buckets`
-    /// for this `Bucketizer`.
-    pub fn bucketize(&self, input: T) -> Option<T> {
-        for bucket in &self.buckets {
-            match *bucket {
-                (None, None, val) =>
-                    return Some(val),
-                (Some(min), None, val) =>
-                    if input >= min {
-                        return Some(val);
-                    },
-                (None, Some(max), val) =>
-                    if input < max {
-                        return Some(val);
-                    },
-                (Some(min), Some(max), val) =>
-                    if input >= min && input < max {
-                        return Some(val);
-                    }
+    /// Add a new bucket like `bucket()`, but first check that it can match something.
+    ///
+    /// Returns `BucketError::MinExceedsMax` if `min` is greater than `max`, or
+    /// `BucketError::Unreachable` if a catch-all bucket with no `min` or `max` was
+    /// already added, since such a bucket would silently never match. This is
+    /// `try_bucket_with_priority()` with a priority of 0.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::{BucketError, Bucketizer};
+    /// let b = Bucketizer::new().try_bucket(Some(0.0), Some(10.0), 5.0).unwrap();
+    /// assert_eq!(b.bucketize(5.0), Some(5.0));
+    ///
+    /// let reversed = b.try_bucket(Some(20.0), Some(10.0), 15.0);
+    /// assert_eq!(reversed, Err(BucketError::MinExceedsMax { index: 1 }));
+    /// ```
+    pub fn try_bucket(self, min: Option<T>, max: Option<T>, value: V) -> Result<Self, BucketError> {
+        self.try_bucket_with_priority(min, max, value, 0)
+    }
+
+    /// Add a new bucket to the `Bucketizer` in place. This behaves exactly like
+    /// `bucket()`, but is more convenient when building buckets in a loop.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new();
+    /// for &(min, max, value) in &[(0.0, 10.0, 5.0), (10.0, 20.0, 15.0)] {
+    ///     b.add_bucket(Some(min), Some(max), value);
+    /// }
+    ///
+    /// assert_eq!(b.bucketize(12.0), Some(15.0));
+    /// ```
+    pub fn add_bucket(&mut self, min: Option<T>, max: Option<T>, value: V) {
+        self.buckets.push(Entry::new((min, max, value), true, false));
+    }
+
+    /// Add a new bucket whose `max` is inclusive. Consumes and returns the `Bucketizer`
+    /// so it can be chained.
+    ///
+    /// A value fits in this bucket if it is greater than or equal to `min` and less
+    /// than or equal to `max`, if each is present. Otherwise it behaves like `bucket()`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(90.0), 0.0)
+    ///     .bucket_inclusive(Some(90.0), Some(100.0), 90.0);
+    ///
+    /// assert_eq!(b.bucketize(100.0), Some(90.0));
+    /// assert_eq!(b.bucketize(100.5), None);
+    /// ```
+    pub fn bucket_inclusive(self, min: Option<T>, max: Option<T>, value: V) -> Self {
+        let mut new = self;
+        new.buckets.push(Entry::new((min, max, value), true, true));
+        new
+    }
+
+    /// Add a new bucket like `bucket()`, along with a human-readable `label` for it,
+    /// which `bucketize_labeled()` produces alongside its value.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .labeled_bucket(Some(0.0), Some(25.0), 0.0, "mild")
+    ///     .labeled_bucket(Some(25.0), None, 25.0, "hot");
+    ///
+    /// assert_eq!(b.bucketize_labeled(30.0), Some((25.0, Some("hot"))));
+    /// ```
+    pub fn labeled_bucket(self, min: Option<T>, max: Option<T>, value: V, label: &'static str) -> Self {
+        let mut new = self;
+        let mut entry = Entry::new((min, max, value), true, false);
+        entry.label = Some(label);
+        new.buckets.push(entry);
+        new
+    }
+
+    /// Add a new bucket like `bucket()`, but with a `priority` for deciding between
+    /// overlapping buckets. A value that falls into several buckets goes to the one
+    /// with the highest priority, and only between buckets of equal priority to the
+    /// one added first. Buckets added any other way have a priority of 0.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(100.0), "any")
+    ///     .bucket_with_priority(Some(40.0), Some(60.0), "specific", 1);
+    ///
+    /// assert_eq!(b.bucketize(50.0), Some("specific"));
+    /// assert_eq!(b.bucketize(70.0), Some("any"));
+    /// ```
+    pub fn bucket_with_priority(self, min: Option<T>, max: Option<T>, value: V, priority: i32) -> Self {
+        let mut new = self;
+        let mut entry = Entry::new((min, max, value), true, false);
+        entry.priority = priority;
+        new.buckets.push(entry);
+        new.max_priority = new.max_priority.max(priority);
+        new
+    }
+
+    /// Add a new bucket like `bucket_with_priority()`, but first check that it can
+    /// match something and doesn't stop any bucket already added from matching.
+    ///
+    /// Returns `BucketError::MinExceedsMax` if `min` is greater than `max`. Returns
+    /// `BucketError::Unreachable` for the new bucket if a catch-all bucket takes
+    /// precedence over it, or for the first bucket already added that the new
+    /// bucket would take precedence over if it is a catch-all.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::{BucketError, Bucketizer};
+    /// let b = Bucketizer::new()
+    ///     .catch_all("other")
+    ///     .try_bucket_with_priority(Some(0.0), Some(10.0), "small", 1)
+    ///     .unwrap();
+    /// assert_eq!(b.bucketize(5.0), Some("small"));
+    ///
+    /// let shadowing = b.try_bucket_with_priority(None, None, "everything", 2);
+    /// assert_eq!(shadowing, Err(BucketError::Unreachable { index: 0 }));
+    /// ```
+    pub fn try_bucket_with_priority(self, min: Option<T>, max: Option<T>, value: V, priority: i32)
+        -> Result<Self, BucketError>
+    {
+        if let (Some(min), Some(max)) = (&min, &max) {
+            if min > max {
+                return Err(BucketError::MinExceedsMax { index: self.len() });
             }
         }
-        None
+        let new = self.bucket_with_priority(min, max, value, priority);
+        let index = new.len() - 1;
+        if new.is_shadowed(index) {
+            return Err(BucketError::Unreachable { index });
+        }
+        let added = &new.buckets[index];
+        if added.is_catch_all() {
+            if let Some(shadowed) = (0..index).find(|&i| added.precedes(index, &new.buckets[i], i)) {
+                return Err(BucketError::Unreachable { index: shadowed });
+            }
+        }
+        Ok(new)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::Bucketizer;
+    /// Whether a catch-all bucket takes precedence over the bucket at `index`, so
+    /// it never matches anything.
+    fn is_shadowed(&self, index: usize) -> bool {
+        let entry = &self.buckets[index];
+        self.buckets.iter().enumerate().any(|(other_index, other)| {
+            other.is_catch_all() && other.precedes(other_index, entry, index)
+        })
+    }
 
-    #[test]
-    fn single_bucket_middle_values() {
-        let bucketizer = Bucketizer::new()
-            .bucket(Some(0.0), Some(1.0), 0.5);
+    /// Add a bucket with no `min` or `max`, which every value falls into. This is
+    /// the same as `bucket(None, None, value)`.
+    ///
+    /// Since it matches everything, a catch-all should be added last; buckets added
+    /// after it never match. `try_bucket()` and `validate()` report any that are.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), "small")
+    ///     .catch_all("other");
+    ///
+    /// assert_eq!(b.bucketize(5.0), Some("small"));
+    /// assert_eq!(b.bucketize(-1e300), Some("other"));
+    /// ```
+    pub fn catch_all(self, value: V) -> Self {
+        self.bucket(None, None, value)
+    }
 
-        assert_eq!(bucketizer.bucketize(0.1), Some(0.5));
-        assert_eq!(bucketizer.bucketize(999.999), None);
+    /// Insert a new bucket at `index`, so it is evaluated before the buckets that
+    /// were at `index` and after. Those buckets shift down to make room. The bucket
+    /// is min-inclusive and max-exclusive, like one added with `bucket()`.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than `len()`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    /// b.insert(0, Some(4.0), Some(6.0), -1.0);
+    ///
+    /// assert_eq!(b.bucketize(5.0), Some(-1.0));
+    /// assert_eq!(b.bucketize(1.0), Some(5.0));
+    /// ```
+    pub fn insert(&mut self, index: usize, min: Option<T>, max: Option<T>, value: V) {
+        self.buckets.insert(index, Entry::new((min, max, value), true, false));
     }
 
-    #[test]
-    fn single_bucket_end_values() {
-        let bucketizer = Bucketizer::new()
-            .bucket(Some(0.0), Some(1.0), 0.5);
+    /// Split the bucket at `index` in two at `at`: one bucket up to but not including
+    /// `at`, followed by one from `at` on. Both keep the original's value, and
+    /// together they match exactly the same values the original did.
+    ///
+    /// Returns `BucketError::SplitOutsideBucket` if there is no bucket at `index`,
+    /// or if `at` isn't inside it, so that either half would match nothing.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    /// b.split(0, 5.0).unwrap();
+    ///
+    /// assert_eq!(b[0], (Some(0.0), Some(5.0), 5.0));
+    /// assert_eq!(b[1], (Some(5.0), Some(10.0), 5.0));
+    /// ```
+    pub fn split(&mut self, index: usize, at: T) -> Result<(), BucketError> {
+        let inside = self.buckets.get(index).is_some_and(|entry| {
+            entry.contains(&at) && entry.bucket.0.as_ref() != Some(&at)
+        });
+        if !inside {
+            return Err(BucketError::SplitOutsideBucket { index });
+        }
+        let mut upper = self.buckets[index].clone();
+        upper.bucket.0 = Some(at.clone());
+        upper.min_inclusive = true;
+        let lower = &mut self.buckets[index];
+        lower.bucket.1 = Some(at);
+        lower.max_inclusive = false;
+        self.buckets.insert(index + 1, upper);
+        Ok(())
+    }
 
-        assert_eq!(bucketizer.bucketize(0.0), Some(0.5));
-        assert_eq!(bucketizer.bucketize(1.0), None);
+    /// Remove the bucket at `index` and return it as `(min, max, value)`, or `None` if
+    /// there is no such bucket. Later buckets shift down to fill its place, keeping
+    /// their order of evaluation.
+    pub fn remove(&mut self, index: usize) -> Option<Bucket<T, V>> {
+        if index < self.buckets.len() {
+            Some(self.buckets.remove(index).bucket)
+        } else {
+            None
+        }
     }
 
-    #[test]
-    fn multiple_buckets_closed_ends() {
-        let bucketizer = Bucketizer::new()
-            .bucket(Some(-1.0), Some(0.0), -0.5)
-            .bucket(Some(0.0), Some(1.0), 0.5);
+    /// Keep only the buckets for which `f` returns `true`, given each bucket as
+    /// `(min, max, value)`. The remaining buckets keep their order of evaluation.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 0.0)
+    ///     .bucket(Some(10.0), Some(20.0), 15.0);
+    /// b.retain(|&(_, _, value)| value > 0.0);
+    ///
+    /// assert_eq!(b.len(), 1);
+    /// ```
+    pub fn retain<F: FnMut(&Bucket<T, V>) -> bool>(&mut self, mut f: F) {
+        self.buckets.retain(|entry| f(&entry.bucket));
+    }
 
-        assert_eq!(bucketizer.bucketize(0.0), Some(0.5));
-        assert_eq!(bucketizer.bucketize(-0.7), Some(-0.5));
-        assert_eq!(bucketizer.bucketize(1.0), None);
+    /// Narrow the buckets to the window from `lo` up to but not including `hi`:
+    /// buckets entirely outside it are removed, and the bounds of the rest are
+    /// clamped to its edges, with open bounds becoming `lo` or `hi`.
+    ///
+    /// Afterwards, values inside the window fall into the same buckets as before,
+    /// and values outside it match nothing. If `hi` isn't greater than `lo`, every
+    /// bucket is removed.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new()
+    ///     .bucket(None, Some(10.0), 5.0)
+    ///     .bucket(Some(20.0), None, 25.0);
+    /// b.clip(0.0, 15.0);
+    ///
+    /// assert_eq!(b.len(), 1);
+    /// assert_eq!(b[0], (Some(0.0), Some(10.0), 5.0));
+    /// ```
+    pub fn clip(&mut self, lo: T, hi: T) {
+        for entry in &mut self.buckets {
+            let clamp_min = match entry.bucket.0 {
+                None => true,
+                Some(ref min) => *min < lo,
+            };
+            if clamp_min {
+                entry.bucket.0 = Some(lo.clone());
+                entry.min_inclusive = true;
+            }
+            let clamp_max = match entry.bucket.1 {
+                None => true,
+                Some(ref max) => *max > hi || (*max == hi && entry.max_inclusive),
+            };
+            if clamp_max {
+                entry.bucket.1 = Some(hi.clone());
+                entry.max_inclusive = false;
+            }
+        }
+        self.buckets.retain(|entry| !entry.is_empty());
     }
 
-    #[test]
-    fn multiple_buckets_opened_ends() {
-        let bucketizer = Bucketizer::new()
-            .bucket(Some(0.0), Some(1.0), 0.5)
-            .bucket(Some(1.0), None, 1.5);
+    /// Reverse the order buckets are evaluated in, so the last one added is checked
+    /// first. For buckets that overlap, this changes which one a value falls into.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(100.0), "any")
+    ///     .bucket(Some(40.0), Some(60.0), "specific");
+    /// b.reverse();
+    ///
+    /// assert_eq!(b.bucketize(50.0), Some("specific"));
+    /// ```
+    pub fn reverse(&mut self) {
+        self.buckets.reverse();
+    }
 
-        assert_eq!(bucketizer.bucketize(0.0), Some(0.5));
-        assert_eq!(bucketizer.bucketize(-0.7), None);
-        assert_eq!(bucketizer.bucketize(1.0), Some(1.5));
+    /// Remove all buckets and any fallback set with `default_value()`, leaving the
+    /// `Bucketizer` as if it were just created.
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.default = None;
+        self.max_priority = 0;
+    }
+
+    /// Free any memory held for buckets beyond those configured, such as after
+    /// `retain()` or `dedup()` have removed many of them.
+    pub fn shrink_to_fit(&mut self) {
+        self.buckets.shrink_to_fit();
+    }
+
+    /// The number of buckets configured.
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Whether no buckets have been configured. An empty `Bucketizer` never
+    /// matches anything.
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// The number of buckets there is room for without allocating more memory.
+    pub fn capacity(&self) -> usize {
+        self.buckets.capacity()
+    }
+
+    /// Iterate over the configured buckets in the order they are evaluated.
+    ///
+    /// Each bucket is yielded as the `(min, max, value)` tuple it was added with.
+    /// The tuple doesn't record whether `min` or `max` is inclusive.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(10.0), None, 10.0);
+    ///
+    /// let buckets: Vec<_> = b.iter().collect();
+    /// assert_eq!(buckets, vec![(Some(0.0), Some(10.0), 5.0), (Some(10.0), None, 10.0)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, V> {
+        Iter {
+            entries: self.buckets.iter()
+        }
+    }
+
+    /// Describe every bucket in evaluation order, including whether its bounds are
+    /// inclusive, which the `(min, max, value)` tuples from `iter()` leave out.
+    ///
+    /// An open bound is never inclusive.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket_inclusive(Some(0.0), Some(10.0), 5.0);
+    /// let range = &b.as_ranges()[0];
+    ///
+    /// assert_eq!((range.lower, range.upper), (Some(0.0), Some(10.0)));
+    /// assert!(range.inclusive_lower && range.inclusive_upper);
+    /// ```
+    pub fn as_ranges(&self) -> Vec<RangeSpec<T, V>> {
+        self.buckets.iter()
+            .map(|entry| {
+                let (ref min, ref max, ref value) = entry.bucket;
+                RangeSpec {
+                    lower: min.clone(),
+                    upper: max.clone(),
+                    inclusive_lower: min.is_some() && entry.min_inclusive,
+                    inclusive_upper: max.is_some() && entry.max_inclusive,
+                    value: value.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// The first bucket configured, which is evaluated first, as `(min, max, value)`,
+    /// or `None` if there are no buckets.
+    pub fn first(&self) -> Option<&Bucket<T, V>> {
+        self.buckets.first().map(|entry| &entry.bucket)
+    }
+
+    /// The last bucket configured, which is evaluated last, as `(min, max, value)`,
+    /// or `None` if there are no buckets.
+    pub fn last(&self) -> Option<&Bucket<T, V>> {
+        self.buckets.last().map(|entry| &entry.bucket)
+    }
+
+    /// Get the bucket at `index` as a mutable `(min, max, value)` tuple, or `None` if
+    /// there is no such bucket, to adjust its bounds or value in place. Whether each
+    /// bound is inclusive stays as it was.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    /// if let Some(bucket) = b.get_mut(0) {
+    ///     bucket.1 = Some(20.0);
+    /// }
+    ///
+    /// assert_eq!(b.bucketize(15.0), Some(5.0));
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Bucket<T, V>> {
+        self.buckets.get_mut(index).map(|entry| &mut entry.bucket)
+    }
+
+    /// Add a new bucket covering `range`. `.range(0.0..10.0, 5.0)` is the same as
+    /// `.bucket(Some(0.0), Some(10.0), 5.0)`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .range(0.0..10.0, 5.0)
+    ///     .range(10.0..20.0, 15.0);
+    ///
+    /// assert_eq!(b.bucketize(10.0), Some(15.0));
+    /// assert_eq!(b.bucketize(20.0), None);
+    /// ```
+    pub fn range(self, range: Range<T>, value: V) -> Self {
+        self.bucket(Some(range.start), Some(range.end), value)
+    }
+
+    /// Add a new bucket covering `range`, including its end. `.range_inclusive(0.0..=10.0, 5.0)`
+    /// is the same as `.bucket_inclusive(Some(0.0), Some(10.0), 5.0)`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().range_inclusive(90.0..=100.0, 95.0);
+    ///
+    /// assert_eq!(b.bucketize(100.0), Some(95.0));
+    /// ```
+    pub fn range_inclusive(self, range: RangeInclusive<T>, value: V) -> Self {
+        let (start, end) = range.into_inner();
+        self.bucket_inclusive(Some(start), Some(end), value)
+    }
+
+    /// Add a new bucket covering `range`, which can be any of Rust's range types.
+    /// Consumes and returns the `Bucketizer` so it can be chained.
+    ///
+    /// Unbounded ends are open, and each bound is inclusive or exclusive just as it
+    /// is in `range`, so `..` matches everything, `a..` is the same as
+    /// `.bucket(Some(a), None, value)`, and `a..=b` includes `b`. A `(Bound, Bound)`
+    /// pair can be used for combinations the range syntax can't express, like an
+    /// exclusive start.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// use std::ops::Bound;
+    ///
+    /// let b = Bucketizer::new()
+    ///     .bucket_range(..0.0, "negative")
+    ///     .bucket_range((Bound::Excluded(0.0), Bound::Included(1.0)), "small")
+    ///     .bucket_range(1.0.., "large");
+    ///
+    /// assert_eq!(b.bucketize(0.0), None);
+    /// assert_eq!(b.bucketize(1.0), Some("small"));
+    /// assert_eq!(b.bucketize(2.0), Some("large"));
+    /// ```
+    pub fn bucket_range<R: RangeBounds<T>>(self, range: R, value: V) -> Self {
+        self.bucket_bounds(range.start_bound().cloned(), range.end_bound().cloned(), value)
+    }
+
+    /// Add a new bucket from `min` to `max`, where each edge is included, excluded
+    /// or unbounded. Consumes and returns the `Bucketizer` so it can be chained.
+    ///
+    /// This can express every combination of inclusive and exclusive edges:
+    /// `bucket()` is the same as `Included(min)` to `Excluded(max)`, and
+    /// `bucket_inclusive()` the same as `Included(min)` to `Included(max)`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// use std::ops::Bound::{Excluded, Included, Unbounded};
+    ///
+    /// let b = Bucketizer::new()
+    ///     .bucket_bounds(Excluded(0.0), Included(1.0), "small")
+    ///     .bucket_bounds(Excluded(1.0), Unbounded, "large");
+    ///
+    /// assert_eq!(b.bucketize(0.0), None);
+    /// assert_eq!(b.bucketize(1.0), Some("small"));
+    /// assert_eq!(b.bucketize(1.5), Some("large"));
+    /// ```
+    pub fn bucket_bounds(self, min: Bound<T>, max: Bound<T>, value: V) -> Self {
+        let (min, min_inclusive) = match min {
+            Bound::Included(min) => (Some(min), true),
+            Bound::Excluded(min) => (Some(min), false),
+            Bound::Unbounded => (None, true),
+        };
+        let (max, max_inclusive) = match max {
+            Bound::Included(max) => (Some(max), true),
+            Bound::Excluded(max) => (Some(max), false),
+            Bound::Unbounded => (None, false),
+        };
+        let mut new = self;
+        new.buckets.push(Entry::new((min, max, value), min_inclusive, max_inclusive));
+        new
+    }
+
+    /// Append `other`'s buckets after this `Bucketizer`'s, keeping both in their
+    /// order of evaluation.
+    ///
+    /// This is concatenation, not a union of intervals: buckets that overlap are
+    /// all kept, and since buckets are evaluated in order, a value matching buckets
+    /// from both resolves to this `Bucketizer`'s bucket. Likewise, this
+    /// `Bucketizer`'s `default_value()` is kept if it has one, and `other`'s if not.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let overrides = Bucketizer::new().bucket(Some(0.0), Some(1.0), "tiny");
+    /// let base = Bucketizer::new().bucket(Some(0.0), Some(10.0), "small");
+    /// let b = overrides.merge(base);
+    ///
+    /// assert_eq!(b.bucketize(0.5), Some("tiny"));
+    /// assert_eq!(b.bucketize(5.0), Some("small"));
+    /// ```
+    pub fn merge(self, other: Bucketizer<T, V>) -> Self {
+        let mut new = self;
+        new.buckets.extend(other.buckets);
+        new.default = new.default.or(other.default);
+        new.max_priority = new.max_priority.max(other.max_priority);
+        new
+    }
+
+    /// Transform every bucket's value with `f`, and the fallback from
+    /// `default_value()` if there is one, keeping the buckets' bounds as they
+    /// are. This is useful for unit conversion or relabeling without rebuilding.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let seconds = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 0.5)
+    ///     .bucket(Some(10.0), None, 2.0);
+    /// let millis = seconds.map_values(|v| v * 1000.0);
+    ///
+    /// assert_eq!(millis.bucketize(20.0), Some(2000.0));
+    /// ```
+    pub fn map_values<U: Clone, F: FnMut(V) -> U>(self, mut f: F) -> Bucketizer<T, U> {
+        Bucketizer {
+            buckets: self.buckets.into_iter()
+                .map(|entry| {
+                    let (min, max, value) = entry.bucket;
+                    Entry {
+                        bucket: (min, max, f(value)),
+                        min_inclusive: entry.min_inclusive,
+                        max_inclusive: entry.max_inclusive,
+                        label: entry.label,
+                        priority: entry.priority,
+                    }
+                })
+                .collect(),
+            default: self.default.map(f),
+            max_priority: self.max_priority,
+        }
+    }
+
+    /// Get the bucketized value of `input` based on the previously configured `buckets`
+    /// for this `Bucketizer`.
+    ///
+    /// An `input` that can't be compared, like `f64::NAN`, doesn't match any bucket
+    /// with a `min` or `max`, so unless a bucket has both bounds open it produces
+    /// `None`. Use `bucketize_checked()` to tell this apart from an ordinary miss.
+    pub fn bucketize(&self, input: T) -> Option<V> {
+        self.bucketize_ref(input).cloned()
+    }
+
+    /// Get a reference to the bucketized value of `input`, without cloning it.
+    /// Otherwise this behaves exactly like `bucketize()`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), String::from("small"));
+    ///
+    /// assert_eq!(b.bucketize_ref(5.0).map(String::as_str), Some("small"));
+    /// ```
+    pub fn bucketize_ref(&self, input: T) -> Option<&V> {
+        self.bucketize_index(input).map(|index| &self.buckets[index].bucket.2)
+    }
+
+    /// Get the bucketized value of `item`'s key, as extracted by `key`. This lets a
+    /// collection of structs be bucketized by one of their fields without collecting
+    /// the keys separately first.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// struct Reading { ts: u64, value: f64 }
+    ///
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), "low");
+    /// let reading = Reading { ts: 1, value: 3.5 };
+    ///
+    /// assert_eq!(b.bucketize_by(&reading, |r| r.value), Some("low"));
+    /// ```
+    pub fn bucketize_by<I, F: Fn(&I) -> T>(&self, item: &I, key: F) -> Option<V> {
+        self.bucketize(key(item))
+    }
+
+    /// Get the bucketized value of `input` along with the matching bucket's label,
+    /// which is `None` unless it was added with `labeled_bucket()`.
+    pub fn bucketize_labeled(&self, input: T) -> Option<(V, Option<&'static str>)> {
+        self.bucketize_index(input).map(|index| {
+            let entry = &self.buckets[index];
+            (entry.bucket.2.clone(), entry.label)
+        })
+    }
+
+    /// Get the whole bucket `input` falls into, as `(min, max, value)`, or `None` if
+    /// it matches no bucket.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.bucketize_full(3.0), Some((Some(0.0), Some(10.0), 5.0)));
+    /// assert_eq!(b.bucketize_full(11.0), None);
+    /// ```
+    pub fn bucketize_full(&self, input: T) -> Option<Bucket<T, V>> {
+        self.bucketize_index(input).map(|index| self.buckets[index].bucket.clone())
+    }
+
+    /// Bucketize every value in `inputs`, returning the results in the same order.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.bucketize_slice(&[1.0, 11.0]), vec![Some(5.0), None]);
+    /// ```
+    pub fn bucketize_slice(&self, inputs: &[T]) -> Vec<Option<V>> {
+        inputs.iter().map(|input| self.bucketize(input.clone())).collect()
+    }
+
+    /// Bucketize every value in `inputs` into `out`, replacing what it held before,
+    /// so one buffer can be reused across batches without reallocating each time.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    /// let mut out = Vec::new();
+    ///
+    /// b.bucketize_into(&[1.0, 11.0], &mut out);
+    /// assert_eq!(out, vec![Some(5.0), None]);
+    /// ```
+    pub fn bucketize_into(&self, inputs: &[T], out: &mut Vec<Option<V>>) {
+        out.clear();
+        out.reserve(inputs.len());
+        out.extend(inputs.iter().map(|input| self.bucketize(input.clone())));
+    }
+
+    /// Collect every value in `inputs` that falls into no bucket, in the same order
+    /// and including duplicates.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.unmatched(vec![1.0, 11.0, -1.0, 5.0]), vec![11.0, -1.0]);
+    /// ```
+    pub fn unmatched<I: IntoIterator<Item = T>>(&self, inputs: I) -> Vec<T> {
+        inputs.into_iter()
+            .filter(|input| !self.contains(input.clone()))
+            .collect()
+    }
+
+    /// Lazily bucketize each value produced by `inputs`. Nothing is pulled from
+    /// `inputs` until the returned iterator is advanced.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0), Some(10), "small");
+    ///
+    /// let mut results = b.bucketize_iter(5..);
+    /// assert_eq!(results.next(), Some(Some("small")));
+    /// assert_eq!(results.nth(4), Some(None));
+    /// ```
+    pub fn bucketize_iter<'a, I>(&'a self, inputs: I) -> impl Iterator<Item = Option<V>> + 'a
+        where I: IntoIterator<Item = T>,
+              I::IntoIter: 'a
+    {
+        inputs.into_iter().map(move |input| self.bucketize(input))
+    }
+
+    /// Get the index of the bucket `input` falls into, counting from zero in the
+    /// order buckets were added. Matching works exactly as in `bucketize()`, so
+    /// this is the first bucket that matches, unless a later one added with
+    /// `bucket_with_priority()` has a higher priority.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(5.0), Some(20.0), 15.0);
+    ///
+    /// assert_eq!(b.bucketize_index(7.0), Some(0));
+    /// assert_eq!(b.bucketize_index(12.0), Some(1));
+    /// assert_eq!(b.bucketize_index(25.0), None);
+    /// ```
+    pub fn bucketize_index(&self, input: T) -> Option<usize> {
+        let mut best: Option<(usize, i32)> = None;
+        for (index, entry) in self.buckets.iter().enumerate() {
+            if best.map_or(true, |(_, priority)| entry.priority > priority) && entry.contains(&input) {
+                if entry.priority == self.max_priority {
+                    return Some(index);
+                }
+                best = Some((index, entry.priority));
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+
+    /// Get the values of every bucket `input` falls into, in the order buckets are
+    /// evaluated, rather than stopping at the first. If no buckets overlap, this
+    /// has at most one value.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(5.0), Some(20.0), 15.0);
+    ///
+    /// assert_eq!(b.bucketize_all(7.0), vec![5.0, 15.0]);
+    /// assert!(b.bucketize_all(25.0).is_empty());
+    /// ```
+    pub fn bucketize_all(&self, input: T) -> Vec<V> {
+        self.buckets.iter()
+            .filter(|entry| entry.contains(&input))
+            .map(|entry| entry.bucket.2.clone())
+            .collect()
+    }
+
+    /// Combine the values of every bucket `input` falls into with `f`, starting from
+    /// `init`, rather than taking the first. Returns `None` if `input` matches no
+    /// bucket.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 1.0)
+    ///     .bucket(Some(5.0), Some(20.0), 2.0);
+    ///
+    /// assert_eq!(b.bucketize_reduce(7.0, 0.0, |a, b| a + b), Some(3.0));
+    /// assert_eq!(b.bucketize_reduce(25.0, 0.0, |a, b| a + b), None);
+    /// ```
+    pub fn bucketize_reduce<F: FnMut(V, V) -> V>(&self, input: T, init: V, mut f: F) -> Option<V> {
+        let mut matched = self.buckets.iter().filter(|entry| entry.contains(&input)).peekable();
+        matched.peek()?;
+        Some(matched.fold(init, |acc, entry| f(acc, entry.bucket.2.clone())))
+    }
+
+    /// Whether `input` falls into any bucket. This stops at the first bucket that
+    /// matches, whatever its priority.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert!(b.contains(3.0));
+    /// assert!(!b.contains(10.0));
+    /// ```
+    pub fn contains(&self, input: T) -> bool {
+        self.buckets.iter().any(|entry| entry.contains(&input))
+    }
+
+    /// Count how many buckets `input` falls into, not just the first. More than one
+    /// means buckets overlap at `input`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(5.0), Some(20.0), 15.0);
+    ///
+    /// assert_eq!(b.match_count(7.0), 2);
+    /// assert_eq!(b.match_count(25.0), 0);
+    /// ```
+    pub fn match_count(&self, input: T) -> usize {
+        self.buckets.iter().filter(|entry| entry.contains(&input)).count()
+    }
+
+    /// Get the bucketized value of `input` like `bucketize()`, but report an error if
+    /// `input` can't be compared to anything, like `f64::NAN`, instead of quietly
+    /// returning `None`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::{BucketizeError, Bucketizer};
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.bucketize_checked(3.0), Ok(Some(5.0)));
+    /// assert_eq!(b.bucketize_checked(30.0), Ok(None));
+    /// assert_eq!(b.bucketize_checked(f64::NAN), Err(BucketizeError::NotComparable));
+    /// ```
+    pub fn bucketize_checked(&self, input: T) -> Result<Option<V>, BucketizeError> {
+        if input.partial_cmp(&input).is_none() {
+            return Err(BucketizeError::NotComparable);
+        }
+        Ok(self.bucketize(input))
+    }
+
+    /// Get the bucketized value of `input` like `bucketize()`, but report an error if
+    /// no buckets are configured, instead of quietly returning `None`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::{BucketizeError, Bucketizer};
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.bucketize_strict(30.0), Ok(None));
+    /// assert_eq!(Bucketizer::<f64>::new().bucketize_strict(3.0), Err(BucketizeError::NoBuckets));
+    /// ```
+    pub fn bucketize_strict(&self, input: T) -> Result<Option<V>, BucketizeError> {
+        if self.is_empty() {
+            return Err(BucketizeError::NoBuckets);
+        }
+        Ok(self.bucketize(input))
+    }
+
+    /// Get the bucketized value of `input`, or `default` if no bucket matches.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.bucketize_or(3.0, -1.0), 5.0);
+    /// assert_eq!(b.bucketize_or(30.0, -1.0), -1.0);
+    /// ```
+    pub fn bucketize_or(&self, input: T, default: V) -> V {
+        self.bucketize(input).unwrap_or(default)
+    }
+
+    /// Set a fallback value for `bucketize_or_default()` to produce when no bucket
+    /// matches. Consumes and returns the `Bucketizer` so it can be chained.
+    ///
+    /// Other methods, including `bucketize()`, ignore the fallback.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .default_value(-1.0);
+    ///
+    /// assert_eq!(b.bucketize_or_default(30.0), Some(-1.0));
+    /// assert_eq!(b.bucketize(30.0), None);
+    /// ```
+    pub fn default_value(self, value: V) -> Self {
+        let mut new = self;
+        new.default = Some(value);
+        new
+    }
+
+    /// Get the bucketized value of `input`, or the fallback set with
+    /// `default_value()` if no bucket matches. Returns `None` only if nothing
+    /// matches and no fallback is set.
+    pub fn bucketize_or_default(&self, input: T) -> Option<V> {
+        self.bucketize(input).or_else(|| self.default.clone())
+    }
+
+    /// Get the bucketized value of `input` like `bucketize()`, but if `input` is
+    /// below every bucket, produce the value of the bucket that starts lowest, and
+    /// if it is above every bucket, the value of the bucket that ends highest.
+    ///
+    /// Unlike `bucketize_nearest()`, only the outermost buckets are considered, so
+    /// an `input` that falls in a gap between buckets still produces `None`.
+    /// Buckets that can never match anything are ignored, and when several buckets
+    /// start or end at the same place, the first one added wins.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), "low")
+    ///     .bucket(Some(20.0), Some(30.0), "high");
+    ///
+    /// assert_eq!(b.bucketize_saturating(-5.0), Some("low"));
+    /// assert_eq!(b.bucketize_saturating(50.0), Some("high"));
+    /// assert_eq!(b.bucketize_saturating(15.0), None);
+    /// ```
+    pub fn bucketize_saturating(&self, input: T) -> Option<V> {
+        self.saturating_index(input).map(|index| self.buckets[index].bucket.2.clone())
+    }
+
+    /// The index of the bucket `bucketize_saturating()` produces the value of.
+    fn saturating_index(&self, input: T) -> Option<usize> {
+        if let Some(index) = self.bucketize_index(input.clone()) {
+            return Some(index);
+        }
+        let starts_lower = |entry: &Entry<T, V>, other: &Entry<T, V>| match (&entry.bucket.0, &other.bucket.0) {
+            (None, Some(_)) => true,
+            (Some(min), Some(other_min)) => min < other_min,
+            _ => false,
+        };
+        let ends_higher = |entry: &Entry<T, V>, other: &Entry<T, V>| match (&entry.bucket.1, &other.bucket.1) {
+            (None, Some(_)) => true,
+            (Some(max), Some(other_max)) => max > other_max,
+            _ => false,
+        };
+        let mut lowest: Option<(usize, &Entry<T, V>)> = None;
+        let mut highest: Option<(usize, &Entry<T, V>)> = None;
+        for (index, entry) in self.buckets.iter().enumerate().filter(|&(_, entry)| !entry.is_empty()) {
+            if lowest.map_or(true, |(_, lowest)| starts_lower(entry, lowest)) {
+                lowest = Some((index, entry));
+            }
+            if highest.map_or(true, |(_, highest)| ends_higher(entry, highest)) {
+                highest = Some((index, entry));
+            }
+        }
+        if let Some((index, lowest)) = lowest {
+            if let Some(ref min) = lowest.bucket.0 {
+                if input < *min || (input == *min && !lowest.min_inclusive) {
+                    return Some(index);
+                }
+            }
+        }
+        if let Some((index, highest)) = highest {
+            if let Some(ref max) = highest.bucket.1 {
+                if input > *max || (input == *max && !highest.max_inclusive) {
+                    return Some(index);
+                }
+            }
+        }
+        None
+    }
+
+    /// Convert into a closure that bucketizes its argument, for passing to
+    /// functions like `Iterator::map()`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// let values: Vec<_> = vec![3.0, 30.0].into_iter().map(b.into_fn()).collect();
+    /// assert_eq!(values, vec![Some(5.0), None]);
+    /// ```
+    pub fn into_fn(self) -> impl Fn(T) -> Option<V> {
+        move |input| self.bucketize(input)
+    }
+
+    /// Convert into a closure that bucketizes its argument, then bucketizes the
+    /// result again with `next`, for two-stage classification. The closure returns
+    /// `None` if either stage matches no bucket.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let coarse = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(100.0), 50.0)
+    ///     .bucket(Some(100.0), None, 150.0);
+    /// let fine = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(100.0), "normal")
+    ///     .bucket(Some(100.0), None, "high");
+    /// let classify = coarse.then(fine);
+    ///
+    /// assert_eq!(classify(120.0), Some("high"));
+    /// assert_eq!(classify(-1.0), None);
+    /// ```
+    pub fn then<W: Clone>(self, next: Bucketizer<V, W>) -> impl Fn(T) -> Option<W>
+        where V: PartialOrd
+    {
+        move |input| self.bucketize(input).and_then(|value| next.bucketize(value))
+    }
+}
+
+impl<T: PartialOrd + Clone + Sub<Output = T>, V: Clone> Bucketizer<T, V> {
+    /// Get the bucketized value of `input` like `bucketize()`, but if no bucket
+    /// matches, fall back to the bucket with an edge closest to `input`.
+    ///
+    /// The distance to a bucket is how far `input` is below its `min` or beyond its
+    /// `max`. When several buckets are equally close, the first one added wins.
+    /// Returns `None` only if there are no buckets, or if `input` can't be compared
+    /// to them at all, like `NaN`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(20.0), Some(30.0), 25.0);
+    ///
+    /// assert_eq!(b.bucketize_nearest(2.0), Some(5.0));
+    /// assert_eq!(b.bucketize_nearest(14.0), Some(5.0));
+    /// assert_eq!(b.bucketize_nearest(16.0), Some(25.0));
+    /// assert_eq!(b.bucketize_nearest(-100.0), Some(5.0));
+    /// ```
+    pub fn bucketize_nearest(&self, input: T) -> Option<V> {
+        if let Some(index) = self.bucketize_index(input.clone()) {
+            return Some(self.buckets[index].bucket.2.clone());
+        }
+        let mut nearest: Option<(T, &V)> = None;
+        for entry in &self.buckets {
+            let (ref min, ref max, ref value) = entry.bucket;
+            let below = match *min {
+                Some(ref min) if input < *min || (input == *min && !entry.min_inclusive) =>
+                    Some(min.clone() - input.clone()),
+                _ => None,
+            };
+            let above = match *max {
+                Some(ref max) if input > *max || (input == *max && !entry.max_inclusive) =>
+                    Some(input.clone() - max.clone()),
+                _ => None,
+            };
+            let distance = match (below, above) {
+                (Some(below), Some(above)) => if above < below { above } else { below },
+                (Some(distance), None) | (None, Some(distance)) => distance,
+                (None, None) => continue,
+            };
+            match nearest {
+                Some((ref closest, _)) if distance >= *closest => {}
+                _ => nearest = Some((distance, value)),
+            }
+        }
+        nearest.map(|(_, value)| value.clone())
+    }
+}
+
+impl<T: PartialOrd + Clone + Add<Output = T>, V: Clone> Bucketizer<T, V> {
+    /// Shift every bucket's bounds by adding `delta` to each `min` and `max` that is
+    /// present. Open bounds stay open, and values are left as they are.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .offset(100.0);
+    ///
+    /// assert_eq!(b.bucketize(105.0), Some(5.0));
+    /// assert_eq!(b.bucketize(5.0), None);
+    /// ```
+    pub fn offset(self, delta: T) -> Self {
+        let mut new = self;
+        for entry in &mut new.buckets {
+            let (ref mut min, ref mut max, _) = entry.bucket;
+            *min = min.take().map(|min| min + delta.clone());
+            *max = max.take().map(|max| max + delta.clone());
+        }
+        new
+    }
+
+    /// Count how many distinct buckets are hit by sampling every `step` from `lo`
+    /// up to but not including `hi`.
+    ///
+    /// The sweep stops before a step would pass `hi`, so it never computes a value
+    /// beyond it, and integer sweeps don't overflow, even across a signed type's
+    /// whole range.
+    ///
+    /// # Panics
+    /// Panics if `step` isn't greater than zero, since the sweep would never end.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0), Some(10), 5)
+    ///     .bucket(Some(10), Some(20), 15)
+    ///     .bucket(Some(20), Some(30), 25);
+    ///
+    /// assert_eq!(b.touched_buckets(0, 20, 5), 2);
+    /// ```
+    pub fn touched_buckets(&self, lo: T, hi: T, step: T) -> usize
+        where T: Sub<Output = T>
+    {
+        let zero = step.clone() - step.clone();
+        assert!(step > zero, "touched_buckets step must be greater than zero");
+        let mut touched = vec![false; self.len()];
+        let mut input = lo;
+        while input < hi {
+            if let Some(index) = self.bucketize_index(input.clone()) {
+                touched[index] = true;
+            }
+            // Whether the next step reaches `hi`, checked so neither subtraction
+            // overflows: `hi - input` when both have the same sign, and otherwise
+            // `hi - step` with `hi` not negative.
+            let last = if input >= zero || hi < zero {
+                hi.clone() - input.clone() <= step
+            } else {
+                input >= hi.clone() - step.clone()
+            };
+            if last {
+                break;
+            }
+            input = input + step.clone();
+        }
+        touched.iter().filter(|&&hit| hit).count()
+    }
+}
+
+impl<T: PartialOrd + Clone + Mul<Output = T>, V: Clone> Bucketizer<T, V> {
+    /// Rescale every bucket's bounds by multiplying each `min` and `max` that is
+    /// present by `factor`, as for a change of units. Open bounds stay open.
+    ///
+    /// Values are left as they are; use `map_values()` to rescale them too. A
+    /// negative `factor` leaves each `min` greater than its `max`, so those buckets
+    /// no longer match anything.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let seconds = Bucketizer::new().bucket(Some(0.0), Some(1.5), "fast");
+    /// let millis = seconds.scale(1000.0);
+    ///
+    /// assert_eq!(millis.bucketize(1200.0), Some("fast"));
+    /// ```
+    pub fn scale(self, factor: T) -> Self {
+        let mut new = self;
+        for entry in &mut new.buckets {
+            let (ref mut min, ref mut max, _) = entry.bucket;
+            *min = min.take().map(|min| min * factor.clone());
+            *max = max.take().map(|max| max * factor.clone());
+        }
+        new
+    }
+}
+
+impl<T, V> Bucketizer<T, V>
+    where T: PartialOrd + Clone + Add<Output = T> + Sub<Output = T> + Div<Output = T> + From<bool>,
+          V: Clone
+{
+    /// Get the midpoint of the bucket `input` falls into, halfway between its `min`
+    /// and `max`, rather than its value. Returns `None` if `input` matches no bucket
+    /// or the bucket has an open bound, so it has no midpoint.
+    ///
+    /// For integer buckets, the midpoint is rounded down, and is computed without
+    /// overflowing even for a bucket spanning a signed type's whole range.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), "low")
+    ///     .bucket(Some(10.0), None, "high");
+    ///
+    /// assert_eq!(b.bucketize_midpoint(3.0), Some(5.0));
+    /// assert_eq!(b.bucketize_midpoint(30.0), None);
+    /// ```
+    pub fn bucketize_midpoint(&self, input: T) -> Option<T> {
+        match self.buckets[self.bucketize_index(input)?].bucket {
+            (Some(ref min), Some(ref max), _) => {
+                let (zero, one) = (T::from(false), T::from(true));
+                let two = one.clone() + one.clone();
+                if (*min < zero) == (*max < zero) {
+                    // With the same sign, `max - min` can't overflow.
+                    Some(min.clone() + (max.clone() - min.clone()) / two)
+                } else {
+                    // With opposite signs, `min + max` can't either, but dividing a
+                    // negative sum rounds it up, so round it back down.
+                    let sum = min.clone() + max.clone();
+                    let half = sum.clone() / two;
+                    if half.clone() + half.clone() > sum {
+                        Some(half - one)
+                    } else {
+                        Some(half)
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone, V: Clone + PartialEq> Bucketizer<T, V> {
+    /// Find the bounds of the first bucket that produces `value`, as `(min, max)`,
+    /// or `None` if no bucket does.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(None, Some(0.0), "freezing")
+    ///     .bucket(Some(0.0), Some(25.0), "mild");
+    ///
+    /// assert_eq!(b.range_for_value("mild"), Some((Some(0.0), Some(25.0))));
+    /// assert_eq!(b.range_for_value("hot"), None);
+    /// ```
+    pub fn range_for_value(&self, value: V) -> Option<(Option<T>, Option<T>)> {
+        self.buckets.iter()
+            .find(|entry| entry.bucket.2 == value)
+            .map(|entry| (entry.bucket.0.clone(), entry.bucket.1.clone()))
+    }
+
+    /// Remove every bucket that is an exact copy of one added before it, with the
+    /// same bounds, inclusivity, value, priority and label, keeping the first copy.
+    /// Buckets that differ only in their label are all kept, since
+    /// `bucketize_labeled()` can tell them apart. The remaining buckets keep their
+    /// order of evaluation.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .bucket(Some(10.0), Some(20.0), 15.0)
+    ///     .bucket(Some(0.0), Some(10.0), 5.0);
+    /// b.dedup();
+    ///
+    /// assert_eq!(b.len(), 2);
+    /// ```
+    pub fn dedup(&mut self) {
+        let mut kept: Vec<Entry<T, V>> = Vec::with_capacity(self.buckets.len());
+        for entry in self.buckets.drain(..) {
+            if !kept.contains(&entry) {
+                kept.push(entry);
+            }
+        }
+        self.buckets = kept;
+    }
+
+    /// Merge each run of consecutive buckets that produce the same value and meet
+    /// end to end, where one's `max` is the next one's `min`, into a single bucket.
+    ///
+    /// Buckets are only merged when no value falls between them, so every input
+    /// falls into a bucket producing the same value as before. A merged bucket
+    /// keeps the label of the first bucket in its run.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let mut b = Bucketizer::new()
+    ///     .bucket(Some(0.0), Some(10.0), "low")
+    ///     .bucket(Some(10.0), Some(20.0), "low")
+    ///     .bucket(Some(20.0), None, "high");
+    /// b.coalesce();
+    ///
+    /// assert_eq!(b.first(), Some(&(Some(0.0), Some(20.0), "low")));
+    /// assert_eq!(b.len(), 2);
+    /// ```
+    pub fn coalesce(&mut self) {
+        let mut kept: Vec<Entry<T, V>> = Vec::with_capacity(self.buckets.len());
+        for entry in self.buckets.drain(..) {
+            if let Some(previous) = kept.last_mut() {
+                if previous.meets(&entry) {
+                    previous.bucket.1 = entry.bucket.1;
+                    previous.max_inclusive = entry.max_inclusive;
+                    continue;
+                }
+            }
+            kept.push(entry);
+        }
+        self.buckets = kept;
+    }
+}
+
+impl Bucketizer<f64> {
+    /// Whether `other` has the same buckets as this one, in the same order, with
+    /// each bound and value within `eps` of its counterpart, for comparing results
+    /// of floating-point arithmetic.
+    ///
+    /// Everything else must match exactly: an open bound only equals an open bound,
+    /// and each bound must be inclusive or exclusive in both.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let a = Bucketizer::new().bucket(Some(0.0), Some(0.3), 1.0);
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(0.1 + 0.2), 1.0);
+    ///
+    /// assert!(a != b);
+    /// assert!(a.approx_eq(&b, 1e-9));
+    /// ```
+    pub fn approx_eq(&self, other: &Self, eps: f64) -> bool {
+        let close = |a: &f64, b: &f64| (a - b).abs() <= eps;
+        let bounds_close = |a: &Option<f64>, b: &Option<f64>| match (a, b) {
+            (None, None) => true,
+            (Some(a), Some(b)) => close(a, b),
+            _ => false,
+        };
+        self.len() == other.len()
+            && bounds_close(&self.default, &other.default)
+            && self.buckets.iter().zip(&other.buckets).all(|(a, b)| {
+                bounds_close(&a.bucket.0, &b.bucket.0)
+                    && bounds_close(&a.bucket.1, &b.bucket.1)
+                    && close(&a.bucket.2, &b.bucket.2)
+                    && a.min_inclusive == b.min_inclusive
+                    && a.max_inclusive == b.max_inclusive
+                    && a.label == b.label
+                    && a.priority == b.priority
+            })
+    }
+}
+
+impl<V: Clone> Bucketizer<usize, V> {
+    /// Get the bucketized value of the length of `items`, such as the bytes of a
+    /// payload or a string.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0), Some(1024), "small")
+    ///     .bucket(Some(1024), None, "large");
+    ///
+    /// assert_eq!(b.bucketize_len("hello".as_bytes()), Some("small"));
+    /// ```
+    pub fn bucketize_len<U>(&self, items: &[U]) -> Option<V> {
+        self.bucketize(items.len())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: PartialOrd + Clone, V: Clone + Eq + Hash> Bucketizer<T, V> {
+    /// Make a copy with each bucket's value replaced by its entry in `table`, and
+    /// values with no entry left as they are. A fallback set with `default_value()`
+    /// is looked up too.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(0), Some(10), 1)
+    ///     .bucket(Some(10), None, 2);
+    /// let table: HashMap<_, _> = vec![(1, 100)].into_iter().collect();
+    ///
+    /// assert_eq!(b.remap_values(&table).bucketize(5), Some(100));
+    /// assert_eq!(b.remap_values(&table).bucketize(15), Some(2));
+    /// ```
+    pub fn remap_values(&self, table: &HashMap<V, V>) -> Self {
+        self.clone().map_values(|value| table.get(&value).cloned().unwrap_or(value))
+    }
+}
+
+impl<T: PartialOrd + Clone, V: Clone> FromIterator<Bucket<T, V>> for Bucketizer<T, V> {
+    /// Build a `Bucketizer` from `(min, max, value)` tuples, as if each were passed
+    /// to `bucket()` in iteration order.
+    fn from_iter<I: IntoIterator<Item = Bucket<T, V>>>(iter: I) -> Self {
+        let mut bucketizer = Bucketizer::new();
+        bucketizer.extend(iter);
+        bucketizer
+    }
+}
+
+impl<T: PartialOrd + Clone, V: Clone> Extend<Bucket<T, V>> for Bucketizer<T, V> {
+    /// Add `(min, max, value)` tuples after the existing buckets, as if each were
+    /// passed to `add_bucket()` in iteration order.
+    fn extend<I: IntoIterator<Item = Bucket<T, V>>>(&mut self, iter: I) {
+        for (min, max, value) in iter {
+            self.add_bucket(min, max, value);
+        }
+    }
+}
+
+impl<'a, T: PartialOrd + Clone, V: Clone> TryFrom<&'a [Bucket<T, V>]> for Bucketizer<T, V> {
+    type Error = BucketError;
+
+    /// Build a `Bucketizer` from a slice of `(min, max, value)` tuples, as if each
+    /// were passed to `bucket()` in order, checking each bucket's bounds first.
+    ///
+    /// Returns `BucketError::MinExceedsMax` for the first bucket whose `min` is
+    /// greater than its `max`.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// # use bucketize::{BucketError, Bucketizer};
+    /// let buckets = [(Some(0.0), Some(10.0), 5.0), (Some(20.0), Some(10.0), 15.0)];
+    ///
+    /// assert!(Bucketizer::try_from(&buckets[..1]).is_ok());
+    /// assert_eq!(Bucketizer::try_from(&buckets[..]), Err(BucketError::MinExceedsMax { index: 1 }));
+    /// ```
+    fn try_from(buckets: &'a [Bucket<T, V>]) -> Result<Self, BucketError> {
+        for (index, bucket) in buckets.iter().enumerate() {
+            if let (Some(min), Some(max), _) = bucket {
+                if min > max {
+                    return Err(BucketError::MinExceedsMax { index });
+                }
+            }
+        }
+        Ok(Bucketizer::with_buckets(buckets.to_vec()))
+    }
+}
+
+impl<'a, T: PartialOrd + Clone, V: Clone> IntoIterator for &'a Bucketizer<T, V> {
+    type Item = Bucket<T, V>;
+    type IntoIter = Iter<'a, T, V>;
+
+    fn into_iter(self) -> Iter<'a, T, V> {
+        self.iter()
+    }
+}
+
+/// Indexing gets the bucket at a position in evaluation order, like `get_mut()`
+/// but read-only. As with `Vec`, it panics if the index is out of range.
+impl<T: PartialOrd + Clone, V: Clone> Index<usize> for Bucketizer<T, V> {
+    type Output = Bucket<T, V>;
+
+    /// The bucket at `index` in evaluation order, as `(min, max, value)`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range.
+    fn index(&self, index: usize) -> &Bucket<T, V> {
+        &self.buckets[index].bucket
+    }
+}
+
+/// An iterator over the buckets of a `Bucketizer`, created by `Bucketizer::iter()`.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T: 'a, V: 'a> {
+    entries: slice::Iter<'a, Entry<T, V>>,
+}
+
+impl<'a, T: Clone, V: Clone> Iterator for Iter<'a, T, V> {
+    type Item = Bucket<T, V>;
+
+    fn next(&mut self) -> Option<Bucket<T, V>> {
+        self.entries.next().map(|entry| entry.bucket.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, T: Clone, V: Clone> DoubleEndedIterator for Iter<'a, T, V> {
+    fn next_back(&mut self) -> Option<Bucket<T, V>> {
+        self.entries.next_back().map(|entry| entry.bucket.clone())
+    }
+}
+
+impl<'a, T: Clone, V: Clone> ExactSizeIterator for Iter<'a, T, V> {}
+
+impl<T: PartialOrd + Clone, V> Entry<T, V> {
+    /// A bucket with no label and the default priority of 0.
+    fn new(bucket: Bucket<T, V>, min_inclusive: bool, max_inclusive: bool) -> Self {
+        Entry { bucket, min_inclusive, max_inclusive, label: None, priority: 0 }
+    }
+
+    /// Whether `input` falls into this bucket: above `min` and below `max`, if each
+    /// is present.
+    fn contains(&self, input: &T) -> bool {
+        match self.bucket {
+            (None, None, _) =>
+                true,
+            (Some(ref min), None, _) =>
+                self.above_min(input, min),
+            (None, Some(ref max), _) =>
+                self.below_max(input, max),
+            (Some(ref min), Some(ref max), _) =>
+                self.above_min(input, min) && self.below_max(input, max)
+        }
+    }
+
+    fn above_min(&self, input: &T, min: &T) -> bool {
+        if self.min_inclusive {
+            input >= min
+        } else {
+            input > min
+        }
+    }
+
+    fn below_max(&self, input: &T, max: &T) -> bool {
+        if self.max_inclusive {
+            input <= max
+        } else {
+            input < max
+        }
+    }
+
+    /// Whether this bucket has no `min` or `max`, so every value falls into it.
+    fn is_catch_all(&self) -> bool {
+        matches!(self.bucket, (None, None, _))
+    }
+
+    /// Whether no value can fall into this bucket, because its bounds are reversed
+    /// or, unless both are inclusive, equal.
+    fn is_empty(&self) -> bool {
+        match self.bucket {
+            (Some(ref min), Some(ref max), _) => match min.partial_cmp(max) {
+                Some(Ordering::Less) => false,
+                Some(Ordering::Equal) => !(self.min_inclusive && self.max_inclusive),
+                _ => true,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether this bucket, at `index`, wins over `other`, at `other_index`, when a
+    /// value falls into both: it has a higher priority, or the same priority and
+    /// was added first.
+    fn precedes(&self, index: usize, other: &Entry<T, V>, other_index: usize) -> bool {
+        self.priority > other.priority || (self.priority == other.priority && index < other_index)
+    }
+
+    /// Whether `next` picks up exactly where this bucket ends, with the same value,
+    /// so the two can be merged into one without changing any result.
+    fn meets(&self, next: &Entry<T, V>) -> bool
+        where V: PartialEq
+    {
+        if self.is_empty() || next.is_empty() || self.bucket.2 != next.bucket.2 || self.priority != next.priority {
+            return false;
+        }
+        match (&self.bucket.1, &next.bucket.0) {
+            (Some(max), Some(next_min)) =>
+                max == next_min && (self.max_inclusive || next.min_inclusive),
+            _ => false,
+        }
+    }
+
+    /// Whether this bucket starts beyond every value `other` matches.
+    fn starts_after(&self, other: &Entry<T, V>) -> bool {
+        match (&self.bucket.0, &other.bucket.1) {
+            (None, _) | (_, None) => false,
+            (Some(min), Some(other_max)) => match min.partial_cmp(other_max) {
+                Some(Ordering::Greater) => true,
+                Some(Ordering::Equal) => !(self.min_inclusive && other.max_inclusive),
+                _ => false,
+            },
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone, V: Clone + PartialEq> PartialEq for Bucketizer<T, V> {
+    /// Two `Bucketizer`s are equal if they have the same buckets in the same order
+    /// and the same fallback value.
+    fn eq(&self, other: &Self) -> bool {
+        self.buckets == other.buckets && self.default == other.default
+    }
+}
+
+impl<T: PartialOrd + Clone + Eq, V: Clone + Eq> Eq for Bucketizer<T, V> {}
+
+impl<T: PartialOrd + Clone + Hash, V: Clone + Hash> Hash for Bucketizer<T, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.buckets.hash(state);
+        self.default.hash(state);
+    }
+}
+
+impl<T: PartialOrd + Clone, V: Clone> Default for Bucketizer<T, V> {
+    /// Equivalent to `Bucketizer::new()`.
+    fn default() -> Self {
+        Bucketizer::new()
+    }
+}
+
+impl<T, V> fmt::Display for Bucketizer<T, V>
+    where T: PartialOrd + Clone + fmt::Display,
+          V: Clone + fmt::Display
+{
+    /// Write each bucket on its own line in evaluation order, in interval notation
+    /// like `[0, 10) => 5`. Open bounds are written as `-inf` or `inf`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, entry) in self.buckets.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match entry.bucket.0 {
+                Some(ref min) if entry.min_inclusive => write!(f, "[{}", min)?,
+                Some(ref min) => write!(f, "({}", min)?,
+                None => write!(f, "(-inf")?,
+            }
+            match entry.bucket.1 {
+                Some(ref max) if entry.max_inclusive => write!(f, ", {}]", max)?,
+                Some(ref max) => write!(f, ", {})", max)?,
+                None => write!(f, ", inf)")?,
+            }
+            write!(f, " => {}", entry.bucket.2)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BucketError, BucketizeError, Bucketizer};
+
+    #[test]
+    fn single_bucket_middle_values() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5);
+
+        assert_eq!(bucketizer.bucketize(0.1), Some(0.5));
+        assert_eq!(bucketizer.bucketize(999.999), None);
+    }
+
+    #[test]
+    fn single_bucket_end_values() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5);
+
+        assert_eq!(bucketizer.bucketize(0.0), Some(0.5));
+        assert_eq!(bucketizer.bucketize(1.0), None);
+    }
+
+    #[test]
+    fn multiple_buckets_closed_ends() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(-1.0), Some(0.0), -0.5)
+            .bucket(Some(0.0), Some(1.0), 0.5);
+
+        assert_eq!(bucketizer.bucketize(0.0), Some(0.5));
+        assert_eq!(bucketizer.bucketize(-0.7), Some(-0.5));
+        assert_eq!(bucketizer.bucketize(1.0), None);
+    }
+
+    #[test]
+    fn multiple_buckets_opened_ends() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(1.0), None, 1.5);
+
+        assert_eq!(bucketizer.bucketize(0.0), Some(0.5));
+        assert_eq!(bucketizer.bucketize(-0.7), None);
+        assert_eq!(bucketizer.bucketize(1.0), Some(1.5));
+    }
+
+    #[test]
+    fn default_is_empty() {
+        assert_eq!(Bucketizer::<f64>::default(), Bucketizer::new());
+    }
+
+    #[test]
+    fn add_bucket_in_loop() {
+        let rows = vec![(-1.0, 0.0, -0.5), (0.0, 1.0, 0.5), (1.0, 2.0, 1.5)];
+        let mut looped = Bucketizer::new();
+        for &(min, max, value) in &rows {
+            looped.add_bucket(Some(min), Some(max), value);
+        }
+
+        let chained = Bucketizer::new()
+            .bucket(Some(-1.0), Some(0.0), -0.5)
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(1.0), Some(2.0), 1.5);
+
+        assert_eq!(looped, chained);
+        for &input in &[-0.7, 0.0, 0.3, 1.9, 2.0] {
+            assert_eq!(looped.bucketize(input), chained.bucketize(input));
+        }
+    }
+
+    #[test]
+    fn bucketize_or_matched() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5);
+
+        assert_eq!(bucketizer.bucketize_or(0.2, -1.0), 0.5);
+    }
+
+    #[test]
+    fn bucketize_or_unmatched() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5);
+
+        assert_eq!(bucketizer.bucketize_or(1.0, -1.0), -1.0);
+        assert_eq!(bucketizer.bucketize_or(-3.0, 42.0), 42.0);
+    }
+
+    #[test]
+    fn bucketize_index_second_bucket() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(-1.0), Some(0.0), -0.5)
+            .bucket(Some(0.0), Some(1.0), 0.5);
+
+        assert_eq!(bucketizer.bucketize_index(0.3), Some(1));
+        assert_eq!(bucketizer.bucketize_index(1.0), None);
+    }
+
+    #[test]
+    fn bucketize_index_shadowed_bucket() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(2.0), Some(5.0), 3.5);
+
+        assert_eq!(bucketizer.bucketize_index(3.0), Some(0));
+    }
+
+    #[test]
+    fn inclusive_bucket_includes_max() {
+        let bucketizer = Bucketizer::new()
+            .bucket_inclusive(Some(90.0), Some(100.0), 95.0);
+
+        assert_eq!(bucketizer.bucketize(90.0), Some(95.0));
+        assert_eq!(bucketizer.bucketize(100.0), Some(95.0));
+        assert_eq!(bucketizer.bucketize(100.1), None);
+    }
+
+    #[test]
+    fn exclusive_bucket_excludes_max() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(90.0), Some(100.0), 95.0);
+
+        assert_eq!(bucketizer.bucketize(100.0), None);
+    }
+
+    #[test]
+    fn inclusive_bucket_open_min() {
+        let bucketizer = Bucketizer::new()
+            .bucket_inclusive(None, Some(0.0), -1.0);
+
+        assert_eq!(bucketizer.bucketize(0.0), Some(-1.0));
+        assert_eq!(bucketizer.bucketize(-1000.0), Some(-1.0));
+        assert_eq!(bucketizer.bucketize(0.5), None);
+    }
+
+    #[test]
+    fn str_output_values() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), "negative")
+            .bucket(Some(0.0), Some(1.0), "small")
+            .bucket(Some(1.0), None, "large");
+
+        assert_eq!(bucketizer.bucketize(-0.5), Some("negative"));
+        assert_eq!(bucketizer.bucketize(0.0), Some("small"));
+        assert_eq!(bucketizer.bucketize(10.0), Some("large"));
+        assert_eq!(bucketizer.bucketize_or(f64::NAN, "unknown"), "unknown");
+    }
+
+    #[test]
+    fn string_output_values() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), String::from("single digit"));
+
+        assert_eq!(bucketizer.bucketize(7), Some(String::from("single digit")));
+        assert_eq!(bucketizer.bucketize(10), None);
+    }
+
+    #[test]
+    fn empty_bucketizer_is_empty() {
+        let bucketizer = Bucketizer::<f64>::new();
+
+        assert!(bucketizer.is_empty());
+        assert_eq!(bucketizer.len(), 0);
+    }
+
+    #[test]
+    fn len_counts_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(-1.0), Some(0.0), -0.5)
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket_inclusive(Some(1.0), Some(2.0), 1.5);
+
+        assert!(!bucketizer.is_empty());
+        assert_eq!(bucketizer.len(), 3);
+    }
+
+    #[test]
+    fn iter_yields_buckets_in_order() {
+        let buckets = vec![
+            (None, Some(0.0), -1.0),
+            (Some(0.0), Some(1.0), 0.5),
+            (Some(1.0), None, 1.5),
+        ];
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), -1.0)
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(1.0), None, 1.5);
+
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), buckets);
+        assert_eq!(bucketizer.iter().len(), 3);
+    }
+
+    #[test]
+    fn for_loop_over_reference() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), "low")
+            .bucket(Some(10), Some(20), "high");
+
+        let mut values = Vec::new();
+        for (_, _, value) in &bucketizer {
+            values.push(value);
+        }
+
+        assert_eq!(values, vec!["low", "high"]);
+    }
+
+    #[test]
+    fn collect_from_tuples() {
+        let rows = vec![
+            (Some(10.0), Some(20.0), 15.0),
+            (Some(5.0), Some(10.0), 7.5),
+            (None, Some(4.0), 0.0),
+        ];
+        let collected: Bucketizer<f64> = rows.into_iter().collect();
+
+        let chained = Bucketizer::new()
+            .bucket(Some(10.0), Some(20.0), 15.0)
+            .bucket(Some(5.0), Some(10.0), 7.5)
+            .bucket(None, Some(4.0), 0.0);
+
+        assert_eq!(collected, chained);
+        for &input in &[-3.0, 4.0, 5.0, 12.34, 20.0] {
+            assert_eq!(collected.bucketize(input), chained.bucketize(input));
+        }
+    }
+
+    #[test]
+    fn bucketize_slice_mixed_values() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(1.0), Some(2.0), 1.5);
+
+        let results = bucketizer.bucketize_slice(&[0.2, -1.0, 1.0, 2.0, 1.99]);
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0], Some(0.5));
+        assert_eq!(results[1], None);
+        assert_eq!(results[2], Some(1.5));
+        assert_eq!(results[3], None);
+        assert_eq!(results[4], Some(1.5));
+    }
+
+    #[test]
+    fn bucketize_iter_is_lazy() {
+        use std::cell::Cell;
+        use std::iter;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5);
+        let pulled = Cell::new(0);
+        let inputs = iter::repeat(0.5).take(10).inspect(|_| pulled.set(pulled.get() + 1));
+
+        let mut results = bucketizer.bucketize_iter(inputs);
+        assert_eq!(pulled.get(), 0);
+
+        assert_eq!(results.next(), Some(Some(0.5)));
+        assert_eq!(results.next(), Some(Some(0.5)));
+        assert_eq!(pulled.get(), 2);
+
+        assert_eq!(results.count(), 8);
+        assert_eq!(pulled.get(), 10);
+    }
+
+    #[test]
+    fn clear_removes_all_buckets() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(1.0), None, 1.5)
+            .default_value(-1.0);
+        assert_eq!(bucketizer.bucketize(0.5), Some(0.5));
+
+        bucketizer.clear();
+
+        assert!(bucketizer.is_empty());
+        assert_eq!(bucketizer.bucketize(0.5), None);
+        assert_eq!(bucketizer.bucketize(10.0), None);
+        assert_eq!(bucketizer.bucketize_or_default(10.0), None);
+        assert_eq!(bucketizer, Bucketizer::new());
+    }
+
+    #[test]
+    fn remove_middle_bucket() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(0.0), Some(20.0), 10.0)
+            .bucket(Some(10.0), Some(20.0), 15.0);
+
+        assert_eq!(bucketizer.remove(1), Some((Some(0.0), Some(20.0), 10.0)));
+
+        assert_eq!(bucketizer.len(), 2);
+        assert_eq!(bucketizer.bucketize(5.0), Some(5.0));
+        assert_eq!(bucketizer.bucketize(15.0), Some(15.0));
+        assert_eq!(bucketizer.bucketize_index(15.0), Some(1));
+    }
+
+    #[test]
+    fn remove_out_of_range() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0);
+
+        assert_eq!(bucketizer.remove(1), None);
+        assert_eq!(bucketizer.len(), 1);
+    }
+
+    #[test]
+    fn try_bucket_rejects_reversed_bounds() {
+        let result = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .try_bucket(Some(2.0), Some(1.0), 1.5);
+
+        assert_eq!(result, Err(BucketError::MinExceedsMax { index: 1 }));
+    }
+
+    #[test]
+    fn try_bucket_accepts_valid_bounds() {
+        let checked = Bucketizer::new()
+            .try_bucket(Some(0.0), Some(1.0), 0.5)
+            .and_then(|b| b.try_bucket(Some(1.0), Some(1.0), 1.0))
+            .and_then(|b| b.try_bucket(None, Some(0.0), -0.5))
+            .and_then(|b| b.try_bucket(Some(1.0), None, 1.5))
+            .unwrap();
+        let unchecked = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(1.0), Some(1.0), 1.0)
+            .bucket(None, Some(0.0), -0.5)
+            .bucket(Some(1.0), None, 1.5);
+
+        assert_eq!(checked, unchecked);
+    }
+
+    #[test]
+    fn nearest_snaps_to_either_side() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(20.0), Some(30.0), 25.0);
+
+        assert_eq!(bucketizer.bucketize_nearest(-3.0), Some(5.0));
+        assert_eq!(bucketizer.bucketize_nearest(12.0), Some(5.0));
+        assert_eq!(bucketizer.bucketize_nearest(18.0), Some(25.0));
+        assert_eq!(bucketizer.bucketize_nearest(1e9), Some(25.0));
+    }
+
+    #[test]
+    fn nearest_matches_bucketize_in_range() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(20), None, 25);
+
+        assert_eq!(bucketizer.bucketize_nearest(3), Some(5));
+        assert_eq!(bucketizer.bucketize_nearest(2000), Some(25));
+    }
+
+    #[test]
+    fn nearest_tie_prefers_first_bucket() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(20), Some(30), 25);
+
+        assert_eq!(bucketizer.bucketize_nearest(15), Some(5));
+    }
+
+    #[test]
+    fn nearest_without_buckets() {
+        assert_eq!(Bucketizer::<f64>::new().bucketize_nearest(1.0), None);
+        assert_eq!(Bucketizer::new().bucket(Some(0.0), Some(1.0), 0.5).bucketize_nearest(f64::NAN), None);
+    }
+
+    #[test]
+    fn contains_in_filter() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(2.0), None, 2.5);
+
+        let in_range: Vec<f64> = vec![-1.0, 0.0, 0.5, 1.0, 1.5, 2.0, 100.0].into_iter()
+            .filter(|&x| bucketizer.contains(x))
+            .collect();
+
+        assert_eq!(in_range, vec![0.0, 0.5, 2.0, 100.0]);
+    }
+
+    #[test]
+    fn range_matches_bucket() {
+        let ranged = Bucketizer::new()
+            .range(-1.0..0.0, -0.5)
+            .range(0.0..1.0, 0.5);
+        let bucketed = Bucketizer::new()
+            .bucket(Some(-1.0), Some(0.0), -0.5)
+            .bucket(Some(0.0), Some(1.0), 0.5);
+
+        assert_eq!(ranged, bucketed);
+        assert_eq!(ranged.bucketize(0.0), Some(0.5));
+        assert_eq!(ranged.bucketize(1.0), None);
+    }
+
+    #[test]
+    fn range_inclusive_includes_end() {
+        let bucketizer = Bucketizer::new()
+            .range_inclusive(0..=9, "single digit")
+            .range(10..100, "double digit");
+
+        assert_eq!(bucketizer.bucketize(0), Some("single digit"));
+        assert_eq!(bucketizer.bucketize(9), Some("single digit"));
+        assert_eq!(bucketizer.bucketize(99), Some("double digit"));
+        assert_eq!(bucketizer.bucketize(100), None);
+        assert_eq!(bucketizer, Bucketizer::new()
+            .bucket_inclusive(Some(0), Some(9), "single digit")
+            .bucket(Some(10), Some(100), "double digit"));
+    }
+
+    #[test]
+    fn bucket_range_all_syntaxes() {
+        let bucketizer = Bucketizer::new()
+            .bucket_range(0..10, "a..b")
+            .bucket_range(10..=20, "a..=b")
+            .bucket_range(..-10, "..b")
+            .bucket_range(100.., "a..")
+            .bucket_range(.., "..");
+
+        assert_eq!(bucketizer.bucketize(0), Some("a..b"));
+        assert_eq!(bucketizer.bucketize(10), Some("a..=b"));
+        assert_eq!(bucketizer.bucketize(20), Some("a..=b"));
+        assert_eq!(bucketizer.bucketize(-11), Some("..b"));
+        assert_eq!(bucketizer.bucketize(100), Some("a.."));
+        assert_eq!(bucketizer.bucketize(-10), Some(".."));
+        assert_eq!(bucketizer.bucketize(50), Some(".."));
+    }
+
+    #[test]
+    fn bucket_range_matches_equivalent_builders() {
+        let ranged = Bucketizer::new()
+            .bucket_range(0.0..1.0, 0.5)
+            .bucket_range(1.0..=2.0, 1.5)
+            .bucket_range(..0.0, -0.5)
+            .bucket_range(2.0.., 2.5)
+            .bucket_range(.., 0.0);
+        let built = Bucketizer::new()
+            .range(0.0..1.0, 0.5)
+            .range_inclusive(1.0..=2.0, 1.5)
+            .bucket(None, Some(0.0), -0.5)
+            .bucket(Some(2.0), None, 2.5)
+            .bucket(None, None, 0.0);
+
+        assert_eq!(ranged, built);
+    }
+
+    #[test]
+    fn bucket_range_unbounded_is_catch_all() {
+        let bucketizer = Bucketizer::new().bucket_range(.., 1.0);
+
+        assert_eq!(bucketizer.bucketize(f64::NEG_INFINITY), Some(1.0));
+        assert_eq!(bucketizer.bucketize(0.0), Some(1.0));
+        assert_eq!(bucketizer.bucketize(f64::MAX), Some(1.0));
+    }
+
+    #[test]
+    fn bucket_range_exclusive_start() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .bucket_range((Bound::Excluded(0), Bound::Unbounded), "positive");
+
+        assert_eq!(bucketizer.bucketize(0), None);
+        assert_eq!(bucketizer.bucketize(1), Some("positive"));
+        assert_eq!(bucketizer.bucketize_nearest(0), Some("positive"));
+    }
+
+    #[test]
+    fn nan_is_an_error_when_checked() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(None, None, 0.0);
+
+        assert_eq!(bucketizer.bucketize_checked(f64::NAN), Err(BucketizeError::NotComparable));
+        assert_eq!(bucketizer.bucketize_checked(0.5), Ok(Some(0.5)));
+    }
+
+    #[test]
+    fn nan_is_none_when_unchecked() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(None, Some(0.0), -0.5)
+            .bucket(Some(1.0), None, 1.5);
+
+        assert_eq!(bucketizer.bucketize(f64::NAN), None);
+        assert_eq!(bucketizer.bucket(None, None, 0.0).bucketize(f64::NAN), Some(0.0));
+    }
+
+    #[test]
+    fn string_inputs_are_ordered_lexicographically() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(String::from("h")), "a-g")
+            .bucket(Some(String::from("h")), Some(String::from("q")), "h-p")
+            .bucket(Some(String::from("q")), None, "q-z");
+
+        assert_eq!(bucketizer.bucketize(String::from("apple")), Some("a-g"));
+        assert_eq!(bucketizer.bucketize(String::from("h")), Some("h-p"));
+        assert_eq!(bucketizer.bucketize(String::from("pumpkin")), Some("h-p"));
+        assert_eq!(bucketizer.bucketize(String::from("zebra")), Some("q-z"));
+    }
+
+    #[test]
+    fn non_copy_newtype_inputs() {
+        #[derive(Debug, Clone, PartialEq, PartialOrd)]
+        struct Date {
+            year: u16,
+            month: u8,
+            day: u8,
+        }
+
+        fn date(year: u16, month: u8, day: u8) -> Date {
+            Date { year, month, day }
+        }
+
+        let quarters = Bucketizer::new()
+            .range(date(2024, 1, 1)..date(2024, 4, 1), "Q1")
+            .range(date(2024, 4, 1)..date(2024, 7, 1), "Q2")
+            .range(date(2024, 7, 1)..date(2024, 10, 1), "Q3")
+            .range(date(2024, 10, 1)..date(2025, 1, 1), "Q4");
+
+        assert_eq!(quarters.bucketize(date(2024, 2, 29)), Some("Q1"));
+        assert_eq!(quarters.bucketize(date(2024, 7, 1)), Some("Q3"));
+        assert_eq!(quarters.bucketize(date(2024, 12, 31)), Some("Q4"));
+        assert_eq!(quarters.bucketize(date(2025, 1, 1)), None);
+        assert_eq!(quarters.bucketize_index(date(2024, 5, 5)), Some(1));
+    }
+
+    #[test]
+    fn map_values_doubles_outputs() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(-1.0), Some(0.0), -0.5)
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket_inclusive(Some(1.0), Some(2.0), 1.5);
+        let doubled = bucketizer.clone().map_values(|v| v * 2.0);
+
+        assert_eq!(doubled.bucketize(-0.5), Some(-1.0));
+        assert_eq!(doubled.bucketize(0.0), Some(1.0));
+        assert_eq!(doubled.bucketize(2.0), Some(3.0));
+        assert_eq!(doubled.bucketize(2.5), None);
+        assert_eq!(doubled.gaps(), bucketizer.gaps());
+    }
+
+    #[test]
+    fn map_values_changes_type() {
+        let labeled = Bucketizer::new()
+            .bucket(Some(0), Some(10), 1)
+            .bucket(Some(10), None, 2)
+            .map_values(|v| format!("tier {}", v));
+
+        assert_eq!(labeled.bucketize(15), Some(String::from("tier 2")));
+    }
+
+    #[test]
+    fn retain_positive_values() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(-2.0), Some(-1.0), -1.5)
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(-1.0), Some(0.0), -0.5)
+            .bucket(Some(1.0), Some(2.0), 1.5);
+
+        bucketizer.retain(|&(_, _, value)| value > 0.0);
+
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), vec![
+            (Some(0.0), Some(1.0), 0.5),
+            (Some(1.0), Some(2.0), 1.5),
+        ]);
+        assert_eq!(bucketizer.bucketize(-1.5), None);
+        assert_eq!(bucketizer.bucketize(-0.5), None);
+        assert_eq!(bucketizer.bucketize(1.5), Some(1.5));
+    }
+
+    #[test]
+    fn display_three_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0), "negative")
+            .bucket(Some(0), Some(10), "small")
+            .bucket_inclusive(Some(10), Some(100), "large");
+
+        assert_eq!(bucketizer.to_string(), "(-inf, 0) => negative\n[0, 10) => small\n[10, 100] => large");
+    }
+
+    #[test]
+    fn display_open_and_exclusive_bounds() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .bucket_range((Bound::Excluded(0.5), Bound::Unbounded), 1.5)
+            .bucket(None, None, 0.0);
+
+        assert_eq!(bucketizer.to_string(), "(0.5, inf) => 1.5\n(-inf, inf) => 0");
+        assert_eq!(Bucketizer::<f64>::new().to_string(), "");
+    }
+
+    #[test]
+    fn merge_prefers_first_bucketizer() {
+        let first = Bucketizer::new()
+            .bucket(Some(0), Some(10), 1);
+        let second = Bucketizer::new()
+            .bucket(Some(5), Some(15), 2)
+            .bucket(Some(15), None, 3);
+        let merged = first.merge(second);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged.bucketize(7), Some(1));
+        assert_eq!(merged.bucketize(12), Some(2));
+        assert_eq!(merged.bucketize_index(20), Some(2));
+    }
+
+    #[test]
+    fn with_capacity_is_empty() {
+        let mut bucketizer = Bucketizer::with_capacity(10);
+
+        assert_eq!(bucketizer, Bucketizer::new());
+        assert!(bucketizer.is_empty());
+        bucketizer.add_bucket(Some(0), Some(10), 5);
+        assert_eq!(bucketizer.bucketize(3), Some(5));
+    }
+
+    #[test]
+    fn into_fn_matches_bucketize() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), -1.0)
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket_inclusive(Some(10.0), Some(20.0), 15.0);
+        let f = bucketizer.clone().into_fn();
+
+        for &input in &[-5.0, 0.0, 9.99, 10.0, 20.0, 20.5, f64::NAN] {
+            assert_eq!(f(input), bucketizer.bucketize(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn first_and_last_empty() {
+        let bucketizer = Bucketizer::<f64>::new();
+
+        assert_eq!(bucketizer.first(), None);
+        assert_eq!(bucketizer.last(), None);
+    }
+
+    #[test]
+    fn first_and_last_populated() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), "low")
+            .bucket(Some(10), Some(20), "mid")
+            .bucket(Some(20), None, "high");
+
+        assert_eq!(bucketizer.first(), Some(&(Some(0), Some(10), "low")));
+        assert_eq!(bucketizer.last(), Some(&(Some(20), None, "high")));
+    }
+
+    #[test]
+    fn bounds_inclusive_min_exclusive_max() {
+        use std::ops::Bound::{Excluded, Included};
+
+        let bucketizer = Bucketizer::new().bucket_bounds(Included(0), Excluded(10), 5);
+
+        assert_eq!(bucketizer.bucketize(0), Some(5));
+        assert_eq!(bucketizer.bucketize(10), None);
+        assert_eq!(bucketizer, Bucketizer::new().bucket(Some(0), Some(10), 5));
+    }
+
+    #[test]
+    fn bounds_inclusive_min_inclusive_max() {
+        use std::ops::Bound::Included;
+
+        let bucketizer = Bucketizer::new().bucket_bounds(Included(0), Included(10), 5);
+
+        assert_eq!(bucketizer.bucketize(0), Some(5));
+        assert_eq!(bucketizer.bucketize(10), Some(5));
+        assert_eq!(bucketizer, Bucketizer::new().bucket_inclusive(Some(0), Some(10), 5));
+    }
+
+    #[test]
+    fn bounds_exclusive_min_exclusive_max() {
+        use std::ops::Bound::Excluded;
+
+        let bucketizer = Bucketizer::new().bucket_bounds(Excluded(0), Excluded(10), 5);
+
+        assert_eq!(bucketizer.bucketize(0), None);
+        assert_eq!(bucketizer.bucketize(1), Some(5));
+        assert_eq!(bucketizer.bucketize(9), Some(5));
+        assert_eq!(bucketizer.bucketize(10), None);
+    }
+
+    #[test]
+    fn bounds_exclusive_min_inclusive_max() {
+        use std::ops::Bound::{Excluded, Included};
+
+        let bucketizer = Bucketizer::new().bucket_bounds(Excluded(0.0), Included(10.0), 5.0);
+
+        assert_eq!(bucketizer.bucketize(0.0), None);
+        assert_eq!(bucketizer.bucketize(0.001), Some(5.0));
+        assert_eq!(bucketizer.bucketize(10.0), Some(5.0));
+        assert_eq!(bucketizer.bucketize(10.001), None);
+    }
+
+    #[test]
+    fn unbounded_bounds_are_open() {
+        use std::ops::Bound::{Excluded, Unbounded};
+
+        let bucketizer = Bucketizer::new()
+            .bucket_bounds(Unbounded, Excluded(0), -1)
+            .bucket_bounds(Excluded(0), Unbounded, 1);
+
+        assert_eq!(bucketizer.bucketize(i32::MIN), Some(-1));
+        assert_eq!(bucketizer.bucketize(0), None);
+        assert_eq!(bucketizer.bucketize(i32::MAX), Some(1));
+        assert_eq!(bucketizer.first(), Some(&(None, Some(0), -1)));
+    }
+
+    #[test]
+    fn bucketize_ref_borrows_stored_value() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), vec![1, 2, 3])
+            .bucket(Some(10), None, vec![4]);
+
+        let value = bucketizer.bucketize_ref(12).unwrap();
+        assert_eq!(value, &vec![4]);
+        assert!(std::ptr::eq(value, &bucketizer.last().unwrap().2));
+        assert_eq!(bucketizer.bucketize_ref(-1), None);
+    }
+
+    #[test]
+    fn insert_shadows_later_bucket() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), "base")
+            .bucket(Some(10), None, "high");
+        bucketizer.insert(0, Some(5), Some(15), "override");
+
+        assert_eq!(bucketizer.bucketize(7), Some("override"));
+        assert_eq!(bucketizer.bucketize(12), Some("override"));
+        assert_eq!(bucketizer.bucketize(2), Some("base"));
+        assert_eq!(bucketizer.bucketize_index(20), Some(2));
+    }
+
+    #[test]
+    fn insert_at_end() {
+        let mut bucketizer = Bucketizer::new().bucket(Some(0), Some(10), 5);
+        bucketizer.insert(1, None, None, 0);
+
+        assert_eq!(bucketizer.last(), Some(&(None, None, 0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_out_of_range() {
+        let mut bucketizer = Bucketizer::new().bucket(Some(0), Some(10), 5);
+        bucketizer.insert(2, None, None, 0);
+    }
+
+    #[test]
+    fn bucketize_all_overlapping() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), "first")
+            .bucket(Some(20), Some(30), "second")
+            .bucket(Some(5), Some(25), "third");
+
+        assert_eq!(bucketizer.bucketize_all(7), vec!["first", "third"]);
+        assert_eq!(bucketizer.bucketize_all(22), vec!["second", "third"]);
+        assert_eq!(bucketizer.bucketize_all(2), vec!["first"]);
+        assert!(bucketizer.bucketize_all(40).is_empty());
+    }
+
+    #[test]
+    fn index_by_position() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), None, 15);
+
+        assert_eq!(bucketizer[0], (Some(0), Some(10), 5));
+        assert_eq!(bucketizer[1], (Some(10), None, 15));
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_range() {
+        let bucketizer = Bucketizer::new().bucket(Some(0), Some(10), 5);
+        let _ = &bucketizer[1];
+    }
+
+    #[test]
+    fn extend_appends_in_order() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 1)
+            .bucket(Some(10), Some(20), 2);
+        bucketizer.extend(vec![(Some(5), Some(30), 3), (None, None, 4)]);
+
+        assert_eq!(bucketizer.len(), 4);
+        assert_eq!(bucketizer.bucketize(5), Some(1));
+        assert_eq!(bucketizer.bucketize(15), Some(2));
+        assert_eq!(bucketizer.bucketize(25), Some(3));
+        assert_eq!(bucketizer.bucketize(-5), Some(4));
+    }
+
+    #[test]
+    fn as_ranges_standard_bucket() {
+        use super::RangeSpec;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), None, 15);
+
+        assert_eq!(bucketizer.as_ranges(), vec![
+            RangeSpec { lower: Some(0), upper: Some(10), inclusive_lower: true, inclusive_upper: false, value: 5 },
+            RangeSpec { lower: Some(10), upper: None, inclusive_lower: true, inclusive_upper: false, value: 15 },
+        ]);
+    }
+
+    #[test]
+    fn as_ranges_exclusive_and_open() {
+        use std::ops::Bound::{Excluded, Included, Unbounded};
+
+        let bucketizer = Bucketizer::new()
+            .bucket_bounds(Excluded(0), Included(10), 5)
+            .bucket_bounds(Unbounded, Unbounded, 0);
+        let ranges = bucketizer.as_ranges();
+
+        assert!(!ranges[0].inclusive_lower && ranges[0].inclusive_upper);
+        assert!(!ranges[1].inclusive_lower && !ranges[1].inclusive_upper);
+    }
+
+    #[test]
+    fn default_value_ignored_on_match() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .default_value(-1);
+
+        assert_eq!(bucketizer.bucketize_or_default(3), Some(5));
+    }
+
+    #[test]
+    fn default_value_on_miss() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .default_value(-1);
+
+        assert_eq!(bucketizer.bucketize_or_default(30), Some(-1));
+        assert_eq!(bucketizer.bucketize(30), None);
+        assert_eq!(Bucketizer::new().bucket(Some(0), Some(10), 5).bucketize_or_default(30), None);
+    }
+
+    #[test]
+    fn default_value_survives_map_and_merge() {
+        let base = Bucketizer::new().bucket(Some(0), Some(10), 5).default_value(-1);
+        let merged = Bucketizer::new().bucket(Some(10), Some(20), 15).merge(base);
+
+        assert_eq!(merged.bucketize_or_default(30), Some(-1));
+        assert_eq!(merged.map_values(|v| v * 2).bucketize_or_default(30), Some(-2));
+    }
+
+    #[test]
+    fn try_bucket_after_catch_all() {
+        let result = Bucketizer::new()
+            .bucket(None, None, 0)
+            .try_bucket(Some(0), Some(10), 5);
+
+        assert_eq!(result, Err(BucketError::Unreachable { index: 1 }));
+    }
+
+    #[test]
+    fn saturating_below_range() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(20), "middle")
+            .bucket(Some(0), Some(10), "low")
+            .bucket(Some(20), Some(30), "high");
+
+        assert_eq!(bucketizer.bucketize_saturating(-100), Some("low"));
+        assert_eq!(bucketizer.bucketize_saturating(-1), Some("low"));
+    }
+
+    #[test]
+    fn saturating_above_range() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(20), Some(30), "high")
+            .bucket(Some(0), Some(10), "low")
+            .bucket(Some(5), Some(5), "empty");
+
+        assert_eq!(bucketizer.bucketize_saturating(30), Some("high"));
+        assert_eq!(bucketizer.bucketize_saturating(1000), Some("high"));
+        assert_eq!(bucketizer.bucketize_saturating(15), None);
+    }
+
+    #[test]
+    fn saturating_in_range_matches_bucketize() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), -1.0)
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(20.0), Some(30.0), 25.0);
+
+        for &input in &[-50.0, 0.0, 5.0, 15.0, 25.0, f64::NAN] {
+            assert_eq!(bucketizer.bucketize_saturating(input), bucketizer.bucketize(input), "{}", input);
+        }
+        assert_eq!(bucketizer.bucketize_saturating(35.0), Some(25.0));
+        assert_eq!(Bucketizer::<f64>::new().bucketize_saturating(1.0), None);
+    }
+
+    #[test]
+    fn unmatched_keeps_order_and_duplicates() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(20), None, 25);
+
+        assert_eq!(bucketizer.unmatched(vec![15, 3, -1, 15, 20, 10, 99]), vec![15, -1, 15, 10]);
+        assert!(bucketizer.unmatched(vec![1, 2, 3]).is_empty());
+    }
+
+    #[test]
+    fn hash_map_key() {
+        use std::collections::HashMap;
+
+        let small = Bucketizer::new().bucket(Some(0), Some(10), 5);
+        let large = Bucketizer::new().bucket(Some(0), Some(100), 50);
+        let mut cache = HashMap::new();
+        cache.insert(small.clone(), "small");
+        cache.insert(large, "large");
+
+        assert_eq!(cache.get(&small), Some(&"small"));
+        assert_eq!(cache.get(&Bucketizer::new().bucket(Some(0), Some(100), 50)), Some(&"large"));
+        assert_eq!(cache.get(&Bucketizer::new().bucket_inclusive(Some(0), Some(100), 50)), None);
+    }
+
+    #[test]
+    fn get_mut_changes_max() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket_inclusive(Some(10), Some(20), 15);
+
+        bucketizer.get_mut(0).unwrap().1 = Some(5);
+        bucketizer.get_mut(1).unwrap().0 = Some(5);
+
+        assert_eq!(bucketizer.bucketize(7), Some(15));
+        assert_eq!(bucketizer.bucketize(20), Some(15));
+        assert!(bucketizer.get_mut(2).is_none());
+    }
+
+    #[test]
+    fn reverse_changes_priority() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), "first")
+            .bucket(Some(5), Some(15), "second");
+
+        assert_eq!(bucketizer.bucketize(7), Some("first"));
+        bucketizer.reverse();
+        assert_eq!(bucketizer.bucketize(7), Some("second"));
+        assert_eq!(bucketizer.bucketize(2), Some("first"));
+    }
+
+    #[test]
+    fn labels_returned_with_values() {
+        let bucketizer = Bucketizer::new()
+            .labeled_bucket(None, Some(10.0), 0.0, "cold")
+            .labeled_bucket(Some(10.0), Some(25.0), 10.0, "warm")
+            .bucket(Some(25.0), None, 25.0);
+
+        assert_eq!(bucketizer.bucketize_labeled(5.0), Some((0.0, Some("cold"))));
+        assert_eq!(bucketizer.bucketize_labeled(20.0), Some((10.0, Some("warm"))));
+        assert_eq!(bucketizer.bucketize_labeled(30.0), Some((25.0, None)));
+        assert_eq!(bucketizer.bucketize(20.0), Some(10.0));
+    }
+
+    #[test]
+    fn labels_survive_map_values() {
+        let bucketizer = Bucketizer::new()
+            .labeled_bucket(Some(0), Some(10), 1, "low")
+            .map_values(|v| v * 100);
+
+        assert_eq!(bucketizer.bucketize_labeled(5), Some((100, Some("low"))));
+    }
+
+    #[test]
+    fn range_for_known_and_unknown_values() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0), -1)
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), None, 5);
+
+        assert_eq!(bucketizer.range_for_value(-1), Some((None, Some(0))));
+        assert_eq!(bucketizer.range_for_value(5), Some((Some(0), Some(10))));
+        assert_eq!(bucketizer.range_for_value(7), None);
+    }
+
+    #[test]
+    fn dedup_keeps_first_copy() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), Some(20), 15)
+            .bucket_inclusive(Some(0), Some(10), 5);
+        bucketizer.dedup();
+
+        assert_eq!(bucketizer, Bucketizer::new()
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(0), Some(10), 5)
+            .bucket_inclusive(Some(0), Some(10), 5));
+    }
+
+    #[test]
+    fn dedup_keeps_differently_labeled_copies() {
+        let mut bucketizer = Bucketizer::new()
+            .labeled_bucket(Some(0), Some(10), 5, "low")
+            .labeled_bucket(Some(0), Some(10), 5, "small")
+            .labeled_bucket(Some(0), Some(10), 5, "low")
+            .bucket(Some(0), Some(10), 5);
+        bucketizer.dedup();
+
+        assert_eq!(bucketizer, Bucketizer::new()
+            .labeled_bucket(Some(0), Some(10), 5, "low")
+            .labeled_bucket(Some(0), Some(10), 5, "small")
+            .bucket(Some(0), Some(10), 5));
+        assert_eq!(bucketizer.bucketize_labeled(3), Some((5, Some("low"))));
+    }
+
+    #[test]
+    fn offset_shifts_bounds() {
+        let original = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket(Some(10.0), Some(20.0), 15.0)
+            .bucket(Some(20.0), None, 25.0);
+        let shifted = original.clone().offset(5.0);
+
+        assert_eq!(shifted.bucketize(2.0), None);
+        assert_eq!(shifted.bucketize(7.0), original.bucketize(2.0));
+        assert_eq!(shifted.bucketize(17.0), original.bucketize(12.0));
+        assert_eq!(shifted.bucketize(14.99), Some(5.0));
+        assert_eq!(shifted.last(), Some(&(Some(25.0), None, 25.0)));
+    }
+
+    #[test]
+    fn scale_moves_boundaries() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.5), 1)
+            .bucket(Some(0.5), Some(2.0), 2)
+            .bucket_inclusive(Some(2.0), Some(3.0), 3)
+            .scale(1000.0);
+
+        assert_eq!(bucketizer.bucketize(499.0), Some(1));
+        assert_eq!(bucketizer.bucketize(500.0), Some(2));
+        assert_eq!(bucketizer.bucketize(1999.0), Some(2));
+        assert_eq!(bucketizer.bucketize(3000.0), Some(3));
+        assert_eq!(bucketizer.bucketize(3000.5), None);
+        assert_eq!(bucketizer.first(), Some(&(None, Some(500.0), 1)));
+    }
+
+    #[test]
+    fn strict_without_buckets() {
+        assert_eq!(Bucketizer::<i32>::new().bucketize_strict(5), Err(BucketizeError::NoBuckets));
+    }
+
+    #[test]
+    fn strict_with_buckets() {
+        let bucketizer = Bucketizer::new().bucket(Some(0), Some(10), 5);
+
+        assert_eq!(bucketizer.bucketize_strict(5), Ok(Some(5)));
+        assert_eq!(bucketizer.bucketize_strict(50), Ok(None));
+    }
+
+    #[test]
+    fn bucketize_structs_by_field() {
+        struct Reading {
+            ts: u64,
+            value: f64,
+        }
+
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), "negative")
+            .bucket(Some(0.0), None, "positive");
+        let readings = [
+            Reading { ts: 1, value: -2.5 },
+            Reading { ts: 2, value: 4.0 },
+            Reading { ts: 3, value: f64::NAN },
+        ];
+
+        let classified: Vec<_> = readings.iter()
+            .map(|reading| (reading.ts, bucketizer.bucketize_by(reading, |r| r.value)))
+            .collect();
+        assert_eq!(classified, vec![(1, Some("negative")), (2, Some("positive")), (3, None)]);
+    }
+
+    #[test]
+    fn then_composes_two_stages() {
+        let coarse = Bucketizer::new()
+            .bucket(Some(0), Some(10), 0)
+            .bucket(Some(10), Some(100), 10)
+            .bucket(Some(100), Some(1000), 100);
+        let fine = Bucketizer::new()
+            .bucket(Some(0), Some(10), "ones")
+            .bucket(Some(10), Some(50), "tens");
+        let classify = coarse.then(fine);
+
+        assert_eq!(classify(5), Some("ones"));
+        assert_eq!(classify(55), Some("tens"));
+        assert_eq!(classify(500), None);
+        assert_eq!(classify(5000), None);
+    }
+
+    #[test]
+    fn bucketize_into_reuses_buffer() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), Some(20), 15);
+        let mut out = Vec::new();
+
+        bucketizer.bucketize_into(&[1, 11, 21, 3], &mut out);
+        assert_eq!(out, vec![Some(5), Some(15), None, Some(5)]);
+        let capacity = out.capacity();
+
+        bucketizer.bucketize_into(&[12, -1], &mut out);
+        assert_eq!(out, vec![Some(15), None]);
+        assert_eq!(out.capacity(), capacity);
+    }
+
+    #[test]
+    fn coalesce_merges_adjacent_same_value_buckets() {
+        use std::ops::Bound::{Excluded, Unbounded};
+
+        let original = Bucketizer::new()
+            .bucket(None, Some(0), "negative")
+            .bucket(Some(0), Some(10), "small")
+            .bucket_inclusive(Some(10), Some(20), "small")
+            .bucket_range((Excluded(20), Excluded(30)), "small")
+            .bucket(Some(30), Some(40), "large")
+            .bucket_range((Excluded(40), Unbounded), "large");
+        let mut coalesced = original.clone();
+        coalesced.coalesce();
+
+        assert_eq!(coalesced.len(), 4);
+        assert_eq!(coalesced[1], (Some(0), Some(30), "small"));
+        for input in -5..50 {
+            assert_eq!(coalesced.bucketize(input), original.bucketize(input), "{}", input);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn remap_values_leaves_unmapped_values() {
+        use std::collections::HashMap;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0), 1)
+            .bucket(Some(0), Some(10), 2)
+            .bucket(Some(10), None, 3);
+        let mut table = HashMap::new();
+        table.insert(1, 10);
+        table.insert(3, 30);
+        table.insert(4, 40);
+
+        let remapped = bucketizer.remap_values(&table);
+        assert_eq!(remapped.bucketize(-5), Some(10));
+        assert_eq!(remapped.bucketize(5), Some(2));
+        assert_eq!(remapped.bucketize(15), Some(30));
+        assert_eq!(bucketizer.bucketize(-5), Some(1));
+    }
+
+    #[test]
+    fn bucketize_full_returns_matched_bucket() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), "negative")
+            .bucket(Some(0.0), Some(10.0), "small");
+
+        assert_eq!(bucketizer.bucketize_full(2.5), Some((Some(0.0), Some(10.0), "small")));
+        assert_eq!(bucketizer.bucketize_full(-1.0), Some((None, Some(0.0), "negative")));
+        assert_eq!(bucketizer.bucketize_full(10.0), None);
+    }
+
+    #[test]
+    fn shrink_to_fit_releases_capacity() {
+        let mut bucketizer = Bucketizer::new();
+        for i in 0..100 {
+            bucketizer.add_bucket(Some(i), Some(i + 1), i);
+        }
+        bucketizer.retain(|&(_, _, value)| value < 2);
+        assert!(bucketizer.capacity() >= 100);
+
+        bucketizer.shrink_to_fit();
+        assert!(bucketizer.capacity() < 100);
+        assert_eq!(bucketizer.len(), 2);
+        assert_eq!(bucketizer.bucketize(1), Some(1));
+    }
+
+    #[test]
+    fn higher_priority_bucket_wins_overlap() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(100), "any")
+            .bucket_with_priority(Some(40), Some(60), "specific", 2)
+            .bucket_with_priority(Some(50), Some(70), "narrow", 1)
+            .bucket_with_priority(Some(80), Some(90), "ignored", -1);
+
+        assert_eq!(bucketizer.bucketize(45), Some("specific"));
+        assert_eq!(bucketizer.bucketize(55), Some("specific"));
+        assert_eq!(bucketizer.bucketize(65), Some("narrow"));
+        assert_eq!(bucketizer.bucketize(85), Some("any"));
+        assert_eq!(bucketizer.bucketize_index(55), Some(1));
+    }
+
+    #[test]
+    fn equal_priority_keeps_insertion_order() {
+        let bucketizer = Bucketizer::new()
+            .bucket_with_priority(Some(0), Some(10), "first", 1)
+            .bucket_with_priority(Some(5), Some(15), "second", 1)
+            .bucket(Some(0), Some(20), "plain");
+
+        assert_eq!(bucketizer.bucketize(7), Some("first"));
+        assert_eq!(bucketizer.bucketize(12), Some("second"));
+        assert_eq!(bucketizer.bucketize(17), Some("plain"));
+    }
+
+    #[test]
+    fn priority_tracked_across_merge_and_removal() {
+        let low = Bucketizer::new()
+            .bucket_with_priority(Some(0), Some(10), "negative", -1)
+            .bucket(Some(0), Some(10), "low");
+        let mut merged = low.clone().merge(Bucketizer::new().bucket_with_priority(Some(0), Some(10), "high", 1));
+
+        assert_eq!(low.bucketize(5), Some("low"));
+        assert_eq!(merged.bucketize(5), Some("high"));
+        merged.remove(2);
+        assert_eq!(merged.bucketize(5), Some("low"));
+        assert_eq!(merged, low);
+    }
+
+    #[test]
+    fn approx_eq_tolerates_rounding() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), -1.0)
+            .bucket(Some(0.0), Some(10.0), 5.0);
+        let nudged = Bucketizer::new()
+            .bucket(None, Some(1e-15), -1.0)
+            .bucket(Some(1e-15), Some(10.0), 5.0);
+
+        assert!(bucketizer != nudged);
+        assert!(bucketizer.approx_eq(&nudged, 1e-12));
+        assert!(!bucketizer.approx_eq(&nudged, 1e-16));
+    }
+
+    #[test]
+    fn approx_eq_distinguishes_open_bounds() {
+        let open = Bucketizer::new().bucket(None, Some(10.0), 5.0);
+        let closed = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+        let inclusive = Bucketizer::new().bucket_inclusive(None, Some(10.0), 5.0);
+
+        assert!(open.approx_eq(&open.clone(), 0.0));
+        assert!(!open.approx_eq(&closed, 1e9));
+        assert!(!open.approx_eq(&inclusive, 1e9));
+        assert!(!open.approx_eq(&Bucketizer::new(), 1e9));
+    }
+
+    #[test]
+    fn touched_buckets_counts_distinct_hits() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), -1.0)
+            .bucket(Some(0.0), Some(1.0), 0.5)
+            .bucket(Some(0.5), Some(2.0), 1.5)
+            .bucket(Some(2.0), Some(2.1), 2.05)
+            .bucket(Some(3.0), None, 4.0);
+
+        assert_eq!(bucketizer.touched_buckets(0.0, 3.0, 0.25), 3);
+        assert_eq!(bucketizer.touched_buckets(-1.0, 5.0, 0.5), 5);
+        assert_eq!(bucketizer.touched_buckets(3.0, 0.0, 1.0), 0);
+    }
+
+    #[test]
+    fn touched_buckets_stops_before_overflow() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0u8), Some(100), 0)
+            .bucket(Some(100), Some(200), 1)
+            .bucket(Some(200), None, 2);
+
+        assert_eq!(bucketizer.touched_buckets(0, 255, 100), 3);
+        assert_eq!(bucketizer.touched_buckets(250, 255, 1), 1);
+        assert_eq!(Bucketizer::new().bucket(Some(i32::MAX - 5), None, ()).touched_buckets(i32::MAX - 3, i32::MAX, 2), 1);
+    }
+
+    #[test]
+    fn touched_buckets_sweeps_full_signed_range() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(-100), 0)
+            .bucket(Some(-100), Some(0), 1)
+            .bucket(Some(0), Some(100), 2)
+            .bucket(Some(100), None, 3);
+
+        assert_eq!(bucketizer.touched_buckets(i8::MIN, i8::MAX, 1), 4);
+        assert_eq!(bucketizer.touched_buckets(i8::MIN, i8::MAX, 100), 3);
+        assert_eq!(bucketizer.touched_buckets(i8::MIN, i8::MAX, i8::MAX), 3);
+
+        let wide = Bucketizer::new().bucket(None, Some(0), 0).bucket(Some(0), None, 1);
+        assert_eq!(wide.touched_buckets(i32::MIN, i32::MAX, 1 << 30), 2);
+        assert_eq!(wide.touched_buckets(i32::MIN, -1, i32::MAX), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn touched_buckets_rejects_zero_step() {
+        Bucketizer::new().bucket(Some(0), Some(10), 5).touched_buckets(0, 10, 0);
+    }
+
+    #[test]
+    fn catch_all_matches_everything() {
+        let catch_all = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 1)
+            .catch_all(0);
+        let explicit = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), 1)
+            .bucket(None, None, 0);
+
+        assert_eq!(catch_all, explicit);
+        for &input in &[f64::MIN, -1.0, 5.0, 10.0, f64::MAX, f64::INFINITY, f64::NAN] {
+            assert_eq!(catch_all.bucketize(input), explicit.bucketize(input), "{}", input);
+        }
+        assert_eq!(catch_all.bucketize(f64::NEG_INFINITY), Some(0));
+        assert_eq!(catch_all.try_bucket(Some(20.0), None, 2), Err(BucketError::Unreachable { index: 2 }));
+    }
+
+    #[test]
+    fn split_leaves_no_gap() {
+        let original = Bucketizer::new()
+            .bucket(None, Some(0), -1)
+            .bucket_inclusive(Some(0), Some(10), 5)
+            .bucket(Some(10), None, 15);
+        let mut split = original.clone();
+        split.split(1, 4).unwrap();
+        split.split(2, 10).unwrap();
+
+        assert_eq!(split.len(), 5);
+        assert_eq!(split[1], (Some(0), Some(4), 5));
+        assert_eq!(split[2], (Some(4), Some(10), 5));
+        assert_eq!(split[3], (Some(10), Some(10), 5));
+        assert!(split.gaps().is_empty());
+        for input in -5..20 {
+            assert_eq!(split.bucketize(input), original.bucketize(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn split_rejects_points_outside_bucket() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(None, Some(0), -1);
+
+        assert_eq!(bucketizer.split(0, 0), Err(BucketError::SplitOutsideBucket { index: 0 }));
+        assert_eq!(bucketizer.split(0, 10), Err(BucketError::SplitOutsideBucket { index: 0 }));
+        assert_eq!(bucketizer.split(1, 3), Err(BucketError::SplitOutsideBucket { index: 1 }));
+        assert_eq!(bucketizer.split(2, 3), Err(BucketError::SplitOutsideBucket { index: 2 }));
+        assert_eq!(bucketizer.len(), 2);
+    }
+
+    #[test]
+    fn bucketize_len_classifies_sizes() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), "small")
+            .bucket(Some(10), Some(100), "medium")
+            .bucket(Some(100), None, "large");
+        let long = "x".repeat(200);
+
+        assert_eq!(bucketizer.bucketize_len("abc".as_bytes()), Some("small"));
+        assert_eq!(bucketizer.bucketize_len("fifteen chars!!".as_bytes()), Some("medium"));
+        assert_eq!(bucketizer.bucketize_len(long.as_bytes()), Some("large"));
+        assert_eq!(bucketizer.bucketize_len(&[1u64; 10]), Some("medium"));
+    }
+
+    #[test]
+    fn midpoint_of_closed_bucket() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), Some(10.0), "low")
+            .bucket_inclusive(Some(10.0), Some(15.0), "middle");
+
+        assert_eq!(bucketizer.bucketize_midpoint(9.9), Some(5.0));
+        assert_eq!(bucketizer.bucketize_midpoint(15.0), Some(12.5));
+        assert_eq!(bucketizer.bucketize_midpoint(20.0), None);
+        assert_eq!(Bucketizer::new().bucket(Some(-3), Some(4), ()).bucketize_midpoint(0), Some(0));
+    }
+
+    #[test]
+    fn midpoint_near_integer_limits() {
+        let bytes = Bucketizer::new().bucket(Some(200u8), Some(250), ());
+        let large = Bucketizer::new().bucket_inclusive(Some(i32::MAX - 10), Some(i32::MAX), ());
+
+        assert_eq!(bytes.bucketize_midpoint(210), Some(225));
+        assert_eq!(large.bucketize_midpoint(i32::MAX), Some(i32::MAX - 5));
+    }
+
+    #[test]
+    fn midpoint_of_wide_signed_buckets() {
+        let wide = Bucketizer::new().bucket(Some(-20000i16), Some(20000), ());
+        let full = Bucketizer::new().bucket_inclusive(Some(i8::MIN), Some(i8::MAX), ());
+        let negative = Bucketizer::new().bucket(Some(i8::MIN), Some(-101), ());
+
+        assert_eq!(wide.bucketize_midpoint(0), Some(0));
+        assert_eq!(full.bucketize_midpoint(0), Some(-1));
+        assert_eq!(negative.bucketize_midpoint(-110), Some(-115));
+        assert_eq!(Bucketizer::new().bucket(Some(i32::MIN), Some(i32::MAX), ()).bucketize_midpoint(0), Some(-1));
+        assert_eq!(Bucketizer::new().bucket(Some(-3), Some(2), ()).bucketize_midpoint(0), Some(-1));
+    }
+
+    #[test]
+    fn no_midpoint_for_open_bucket() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), "negative")
+            .bucket(Some(0.0), None, "positive");
+
+        assert_eq!(bucketizer.bucketize_midpoint(-5.0), None);
+        assert_eq!(bucketizer.bucketize_midpoint(5.0), None);
+        assert_eq!(Bucketizer::new().catch_all(0.0).bucketize_midpoint(1.0), None);
+    }
+
+    #[test]
+    fn bucketize_reduce_combines_overlapping_values() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 1)
+            .bucket(Some(5), Some(20), 10)
+            .bucket(Some(15), Some(30), 100);
+
+        assert_eq!(bucketizer.bucketize_reduce(7, 0, |a, b| a + b), Some(11));
+        assert_eq!(bucketizer.bucketize_reduce(2, 0, |a, b| a + b), Some(1));
+        assert_eq!(bucketizer.bucketize_reduce(17, 0, i32::max), Some(100));
+        assert_eq!(bucketizer.bucketize_reduce(40, 0, |a, b| a + b), None);
+    }
+
+    #[test]
+    fn match_count_detects_overlap() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket_inclusive(Some(10), Some(20), 15)
+            .bucket(Some(20), Some(30), 25);
+
+        assert_eq!(bucketizer.match_count(20), 2);
+        assert_eq!(bucketizer.match_count(10), 1);
+        assert_eq!(bucketizer.match_count(25), 1);
+        assert_eq!(bucketizer.match_count(30), 0);
+    }
+
+    #[test]
+    fn with_buckets_matches_chained() {
+        let buckets = vec![
+            (None, Some(0), -1),
+            (Some(0), Some(10), 5),
+            (Some(10), None, 15),
+        ];
+        let bucketizer = Bucketizer::with_buckets(buckets);
+
+        assert_eq!(bucketizer, Bucketizer::new()
+            .bucket(None, Some(0), -1)
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), None, 15));
+        assert_eq!(bucketizer.capacity(), 3);
+    }
+
+    #[test]
+    fn try_from_slice() {
+        use std::convert::TryFrom;
+
+        let buckets = [
+            (None, Some(0.0), -1.0),
+            (Some(0.0), Some(10.0), 5.0),
+            (Some(10.0), None, 15.0),
+        ];
+        let bucketizer = Bucketizer::try_from(&buckets[..]).unwrap();
+
+        assert_eq!(bucketizer, buckets.iter().cloned().collect());
+        assert_eq!(bucketizer.bucketize(12.34), Some(15.0));
+    }
+
+    #[test]
+    fn try_from_rejects_reversed_bounds() {
+        use std::convert::TryFrom;
+
+        let buckets = [
+            (Some(0), Some(10), 5),
+            (Some(10), Some(10), 10),
+            (Some(30), Some(20), 25),
+        ];
+
+        assert_eq!(Bucketizer::try_from(&buckets[..]), Err(BucketError::MinExceedsMax { index: 2 }));
+    }
+
+    #[test]
+    fn clip_to_window() {
+        let original = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(20), Some(30), 25);
+        let mut clipped = original.clone();
+        clipped.clip(5, 15);
+
+        assert_eq!(clipped.iter().collect::<Vec<_>>(), vec![
+            (Some(5), Some(10), 5),
+            (Some(10), Some(15), 15),
+        ]);
+        for input in 0..30 {
+            let expected = if (5..15).contains(&input) { original.bucketize(input) } else { None };
+            assert_eq!(clipped.bucketize(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn clip_open_and_inclusive_bounds() {
+        let mut bucketizer = Bucketizer::new()
+            .bucket(None, Some(0), -1)
+            .bucket_inclusive(Some(0), Some(10), 5)
+            .bucket(Some(10), None, 15);
+        bucketizer.clip(-5, 10);
+
+        assert_eq!(bucketizer.iter().collect::<Vec<_>>(), vec![
+            (Some(-5), Some(0), -1),
+            (Some(0), Some(10), 5),
+        ]);
+        assert_eq!(bucketizer.bucketize(10), None);
+        assert_eq!(bucketizer.bucketize(9), Some(5));
+
+        bucketizer.clip(3, 3);
+        assert!(bucketizer.is_empty());
+    }
+
+    #[test]
+    fn try_bucket_respects_priority() {
+        let bucketizer = Bucketizer::new()
+            .bucket_with_priority(Some(0), Some(10), "low", -1)
+            .catch_all("other");
+
+        assert!(bucketizer.clone().try_bucket_with_priority(Some(20), Some(30), "high", 5).is_ok());
+        assert_eq!(bucketizer.clone().try_bucket(Some(20), Some(30), "high"),
+                   Err(BucketError::Unreachable { index: 2 }));
+        assert_eq!(Bucketizer::new().bucket(Some(0), Some(10), "low").try_bucket_with_priority(None, None, "all", 1),
+                   Err(BucketError::Unreachable { index: 0 }));
+        assert!(Bucketizer::new().bucket(Some(0), Some(10), "low").try_bucket(None, None, "all").is_ok());
     }
 }
@@ -0,0 +1,44 @@
+//! Bucketizing in parallel with `rayon`, behind the `rayon` feature.
+
+use rayon::prelude::*;
+
+use Bucketizer;
+
+impl<T, V> Bucketizer<T, V>
+    where T: PartialOrd + Clone + Sync,
+          V: Clone + Send + Sync
+{
+    /// Bucketize every value in `inputs` in parallel, returning the results in the
+    /// same order, exactly as `bucketize_slice()` would.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new().bucket(Some(0.0), Some(10.0), 5.0);
+    ///
+    /// assert_eq!(b.par_bucketize_slice(&[1.0, 11.0]), vec![Some(5.0), None]);
+    /// ```
+    pub fn par_bucketize_slice(&self, inputs: &[T]) -> Vec<Option<V>> {
+        inputs.par_iter().map(|input| self.bucketize(input.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Bucketizer;
+
+    #[test]
+    fn matches_sequential_order() {
+        let bucketizer = Bucketizer::linear(0.0, 1000.0, 100);
+        // A fixed linear congruential sequence, so inputs are shuffled but repeatable.
+        let mut state: u64 = 42;
+        let inputs: Vec<f64> = (0..200_000)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64 * 1200.0 - 100.0
+            })
+            .collect();
+
+        assert_eq!(bucketizer.par_bucketize_slice(&inputs), bucketizer.bucketize_slice(&inputs));
+    }
+}
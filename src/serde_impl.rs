@@ -0,0 +1,132 @@
+//! `Serialize` and `Deserialize` for `Bucketizer`, behind the `serde` feature.
+
+use alloc::vec::Vec;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use {Bucketizer, Entry};
+
+/// How a single bucket is written out.
+#[derive(Serialize)]
+struct BucketRef<'a, T: 'a, V: 'a> {
+    min: &'a Option<T>,
+    max: &'a Option<T>,
+    value: &'a V,
+    #[serde(skip_serializing_if = "is_false")]
+    min_exclusive: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    max_inclusive: bool,
+    #[serde(skip_serializing_if = "is_zero")]
+    priority: i32,
+}
+
+/// How a single bucket is read back in. `min_exclusive`, `max_inclusive` and
+/// `priority` may be left out, in which case the bucket is min-inclusive and
+/// max-exclusive with a priority of 0, like one added with `bucket()`.
+#[derive(Deserialize)]
+struct BucketDef<T, V> {
+    min: Option<T>,
+    max: Option<T>,
+    value: V,
+    #[serde(default)]
+    min_exclusive: bool,
+    #[serde(default)]
+    max_inclusive: bool,
+    #[serde(default)]
+    priority: i32,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+impl<T, V> Serialize for Bucketizer<T, V>
+    where T: PartialOrd + Clone + Serialize,
+          V: Clone + Serialize
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.buckets.iter().map(|entry| BucketRef {
+            min: &entry.bucket.0,
+            max: &entry.bucket.1,
+            value: &entry.bucket.2,
+            min_exclusive: !entry.min_inclusive,
+            max_inclusive: entry.max_inclusive,
+            priority: entry.priority,
+        }))
+    }
+}
+
+impl<'de, T, V> Deserialize<'de> for Bucketizer<T, V>
+    where T: PartialOrd + Clone + Deserialize<'de>,
+          V: Clone + Deserialize<'de>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let defs = Vec::<BucketDef<T, V>>::deserialize(deserializer)?;
+        let max_priority = defs.iter().map(|def| def.priority).fold(0, i32::max);
+        Ok(Bucketizer {
+            buckets: defs.into_iter()
+                .map(|def| {
+                    let mut entry = Entry::new((def.min, def.max, def.value), !def.min_exclusive, def.max_inclusive);
+                    entry.priority = def.priority;
+                    entry
+                })
+                .collect(),
+            default: None,
+            max_priority,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use std::ops::Bound;
+
+    use Bucketizer;
+
+    #[test]
+    fn round_trip() {
+        let bucketizer = Bucketizer::new()
+            .bucket(None, Some(0.0), -1.0)
+            .bucket(Some(0.0), Some(10.0), 5.0)
+            .bucket_inclusive(Some(10.0), Some(20.0), 15.0)
+            .bucket_range((Bound::Excluded(20.0), Bound::Unbounded), 25.0);
+
+        let json = serde_json::to_string(&bucketizer).unwrap();
+        let parsed: Bucketizer<f64> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, bucketizer);
+    }
+
+    #[test]
+    fn human_editable_form() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0.0), None, "positive");
+
+        assert_eq!(serde_json::to_string(&bucketizer).unwrap(),
+                   r#"[{"min":0.0,"max":null,"value":"positive"}]"#);
+
+        let parsed: Bucketizer<f64, String> =
+            serde_json::from_str(r#"[{"min": null, "max": 1.5, "value": "low"}]"#).unwrap();
+        assert_eq!(parsed.bucketize(-3.0), Some(String::from("low")));
+        assert_eq!(parsed.bucketize(1.5), None);
+    }
+
+    #[test]
+    fn round_trip_keeps_priority() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(100), 1)
+            .bucket_with_priority(Some(40), Some(60), 2, 3);
+
+        let json = serde_json::to_string(&bucketizer).unwrap();
+        assert_eq!(json, r#"[{"min":0,"max":100,"value":1},{"min":40,"max":60,"value":2,"priority":3}]"#);
+
+        let parsed: Bucketizer<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, bucketizer);
+        assert_eq!(parsed.bucketize(50), Some(2));
+    }
+}
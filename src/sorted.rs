@@ -0,0 +1,402 @@
+//! Binary-search lookups for buckets that are sorted and don't overlap.
+
+use std::cmp::Ordering;
+
+use {BucketError, Bucketizer, Entry};
+
+/// A `Bucketizer` whose buckets are known to be in ascending order and not to
+/// overlap, created by `Bucketizer::sorted()`.
+///
+/// Because at most one bucket can match any value, a `SortedBucketizer` finds it
+/// with a binary search rather than checking each bucket in turn, which makes
+/// lookups O(log n) in the number of buckets. Results are always the same as
+/// the `Bucketizer` it was built from.
+///
+/// # Example
+/// ```
+/// use bucketize::Bucketizer;
+///
+/// let b = Bucketizer::new()
+///     .bucket(None, Some(0.0), -1.0)
+///     .bucket(Some(0.0), Some(10.0), 5.0)
+///     .bucket(Some(10.0), Some(20.0), 15.0)
+///     .sorted()
+///     .unwrap();
+///
+/// assert_eq!(b.bucketize(12.34), Some(15.0));
+/// assert_eq!(b.bucketize(9999.99), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortedBucketizer<T: PartialOrd + Clone, V: Clone = T> {
+    inner: Bucketizer<T, V>,
+}
+
+impl<T: PartialOrd + Clone, V: Clone> Bucketizer<T, V> {
+    /// Reorder buckets by ascending `min`, with open `min`s first. Buckets with the
+    /// same `min` keep their relative order, except that an inclusive `min` comes
+    /// before an exclusive one.
+    ///
+    /// This changes the order buckets are evaluated in, so for buckets that overlap
+    /// it may change which one a value falls into. Buckets that don't overlap give
+    /// the same results as before, and once sorted can be passed to `sorted()`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(10.0), None, 10.0)
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .sorted_by_min();
+    ///
+    /// assert_eq!(b.first(), Some(&(Some(0.0), Some(10.0), 5.0)));
+    /// assert!(b.sorted().is_ok());
+    /// ```
+    pub fn sorted_by_min(self) -> Self {
+        let mut new = self;
+        new.buckets.sort_by(|a, b| {
+            if starts_before(a, b) {
+                Ordering::Less
+            } else if starts_before(b, a) {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        });
+        new
+    }
+
+    /// Make an equivalent copy in a more canonical form: buckets that can never
+    /// match are dropped, and if the rest don't overlap, they're sorted with
+    /// `sorted_by_min()`.
+    ///
+    /// A bucket is dropped if no value falls into it, or if it lies entirely within
+    /// another bucket that takes precedence over it, so values in it always go to
+    /// the other bucket. Buckets that still overlap are left in their order, since
+    /// reordering them would change results. This is a best effort: two
+    /// bucketizers that give the same results don't always normalize to the same
+    /// buckets, but every input gives the same result as before.
+    ///
+    /// # Example
+    /// ```
+    /// # use bucketize::Bucketizer;
+    /// let b = Bucketizer::new()
+    ///     .bucket(Some(10.0), Some(20.0), 15.0)
+    ///     .bucket(Some(12.0), Some(18.0), 0.0)
+    ///     .bucket(Some(0.0), Some(10.0), 5.0)
+    ///     .normalize();
+    ///
+    /// assert_eq!(b.len(), 2);
+    /// assert_eq!(b.first(), Some(&(Some(0.0), Some(10.0), 5.0)));
+    /// ```
+    pub fn normalize(&self) -> Self {
+        let shadowed = |index: usize, entry: &Entry<T, V>| {
+            self.buckets.iter().enumerate().any(|(other_index, other)| {
+                other.precedes(other_index, entry, index) && encloses(other, entry)
+            })
+        };
+        let normalized = Bucketizer {
+            buckets: self.buckets.iter().enumerate()
+                .filter(|&(index, entry)| !entry.is_empty() && !shadowed(index, entry))
+                .map(|(_, entry)| entry.clone())
+                .collect(),
+            default: self.default.clone(),
+            max_priority: self.max_priority,
+        };
+        if normalized.overlaps().is_empty() {
+            normalized.sorted_by_min()
+        } else {
+            normalized
+        }
+    }
+
+    /// Check that buckets are in ascending order and don't overlap, and if so,
+    /// convert into a `SortedBucketizer` for faster lookups.
+    ///
+    /// A bucket with an open `min` may only come first, and one with an open `max`
+    /// may only come last. Buckets may touch, as in `[0, 10)` followed by
+    /// `[10, 20)`, but a bucket with an inclusive `max` may not touch the next one.
+    ///
+    /// Returns the first problem found if the buckets can't be sorted this way.
+    pub fn sorted(self) -> Result<SortedBucketizer<T, V>, BucketError> {
+        for (index, entry) in self.buckets.iter().enumerate() {
+            if let (Some(ref min), Some(ref max), _) = entry.bucket {
+                if min > max {
+                    return Err(BucketError::MinExceedsMax { index });
+                }
+            }
+            if index > 0 {
+                let previous = &self.buckets[index - 1];
+                if starts_before(entry, previous) {
+                    return Err(BucketError::Unsorted { index });
+                }
+                if !entry.starts_after(previous) {
+                    return Err(BucketError::Overlapping { first: index - 1, second: index });
+                }
+            }
+        }
+        Ok(SortedBucketizer { inner: self })
+    }
+}
+
+/// Whether `entry` starts strictly before `previous` does.
+fn starts_before<T: PartialOrd + Clone, V>(entry: &Entry<T, V>, previous: &Entry<T, V>) -> bool {
+    match (&entry.bucket.0, &previous.bucket.0) {
+        (None, previous_min) => previous_min.is_some(),
+        (Some(_), None) => false,
+        (Some(min), Some(previous_min)) =>
+            min < previous_min
+                || (min == previous_min && entry.min_inclusive && !previous.min_inclusive),
+    }
+}
+
+/// Whether every value `inner` matches is also matched by `outer`.
+fn encloses<T: PartialOrd + Clone, V>(outer: &Entry<T, V>, inner: &Entry<T, V>) -> bool {
+    let lower = match (&outer.bucket.0, &inner.bucket.0) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(min), Some(inner_min)) => match min.partial_cmp(inner_min) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => outer.min_inclusive || !inner.min_inclusive,
+            _ => false,
+        },
+    };
+    let upper = match (&outer.bucket.1, &inner.bucket.1) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(max), Some(inner_max)) => match max.partial_cmp(inner_max) {
+            Some(Ordering::Greater) => true,
+            Some(Ordering::Equal) => outer.max_inclusive || !inner.max_inclusive,
+            _ => false,
+        },
+    };
+    lower && upper
+}
+
+impl<T: PartialOrd + Clone, V: Clone> SortedBucketizer<T, V> {
+    /// Get the bucketized value of `input`, exactly as `Bucketizer::bucketize()` would.
+    pub fn bucketize(&self, input: T) -> Option<V> {
+        self.bucketize_index(input).map(|index| self.inner.buckets[index].bucket.2.clone())
+    }
+
+    /// Get the index of the bucket `input` falls into, exactly as
+    /// `Bucketizer::bucketize_index()` would.
+    pub fn bucketize_index(&self, input: T) -> Option<usize> {
+        let buckets = &self.inner.buckets;
+        let after = buckets.partition_point(|entry| match entry.bucket.0 {
+            None => true,
+            Some(ref min) => entry.above_min(&input, min),
+        });
+        match after.checked_sub(1) {
+            Some(index) if buckets[index].contains(&input) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// The number of buckets configured.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether no buckets have been configured.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Convert back into the `Bucketizer` this was built from.
+    pub fn into_inner(self) -> Bucketizer<T, V> {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {BucketError, Bucketizer};
+
+    #[test]
+    fn matches_linear_lookup() {
+        let mut bucketizer = Bucketizer::new().bucket(None, Some(0.0), -1.0);
+        for i in 0..100 {
+            let min = f64::from(i) * 2.5;
+            bucketizer.add_bucket(Some(min), Some(min + 2.5), min);
+        }
+        let bucketizer = bucketizer.bucket_inclusive(Some(260.0), Some(300.0), 300.0);
+        let sorted = bucketizer.clone().sorted().unwrap();
+
+        let mut input = -10.0;
+        while input < 310.0 {
+            assert_eq!(sorted.bucketize(input), bucketizer.bucketize(input), "{}", input);
+            assert_eq!(sorted.bucketize_index(input), bucketizer.bucketize_index(input));
+            input += 0.25;
+        }
+        assert_eq!(sorted.bucketize(300.0), Some(300.0));
+        assert_eq!(sorted.bucketize(f64::NAN), None);
+    }
+
+    #[test]
+    fn empty_sorted() {
+        let sorted = Bucketizer::<f64>::new().sorted().unwrap();
+
+        assert!(sorted.is_empty());
+        assert_eq!(sorted.bucketize(1.0), None);
+    }
+
+    #[test]
+    fn rejects_overlap() {
+        let result = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(5), Some(20), 15)
+            .sorted();
+
+        assert_eq!(result, Err(BucketError::Overlapping { first: 0, second: 1 }));
+    }
+
+    #[test]
+    fn rejects_touching_inclusive_max() {
+        let result = Bucketizer::new()
+            .bucket_inclusive(Some(0), Some(10), 5)
+            .bucket(Some(10), Some(20), 15)
+            .sorted();
+
+        assert_eq!(result, Err(BucketError::Overlapping { first: 0, second: 1 }));
+    }
+
+    #[test]
+    fn rejects_open_ends_in_the_middle() {
+        let open_max = Bucketizer::new()
+            .bucket(Some(0), None, 5)
+            .bucket(Some(10), Some(20), 15)
+            .sorted();
+        let open_min = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(None, Some(-5), 15)
+            .sorted();
+
+        assert_eq!(open_max, Err(BucketError::Overlapping { first: 0, second: 1 }));
+        assert_eq!(open_min, Err(BucketError::Unsorted { index: 1 }));
+    }
+
+    #[test]
+    fn rejects_descending() {
+        let result = Bucketizer::new()
+            .bucket(Some(10), Some(20), 15)
+            .bucket(Some(0), Some(10), 5)
+            .sorted();
+
+        assert_eq!(result, Err(BucketError::Unsorted { index: 1 }));
+    }
+
+    #[test]
+    fn rejects_reversed_bounds() {
+        let result = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(20), Some(15), 15)
+            .sorted();
+
+        assert_eq!(result, Err(BucketError::MinExceedsMax { index: 1 }));
+    }
+
+    #[test]
+    fn into_inner_round_trips() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(10), None, 15);
+
+        assert_eq!(bucketizer.clone().sorted().unwrap().into_inner(), bucketizer);
+    }
+
+    #[test]
+    fn exclusive_min_after_inclusive_max() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .range_inclusive(0..=10, 5)
+            .bucket_range((Bound::Excluded(10), Bound::Excluded(20)), 15);
+        let sorted = bucketizer.clone().sorted().unwrap();
+
+        for input in -1..21 {
+            assert_eq!(sorted.bucketize(input), bucketizer.bucketize(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_exclusive_min_before_inclusive_min() {
+        use std::ops::Bound;
+
+        let result = Bucketizer::new()
+            .bucket_range((Bound::Excluded(0), Bound::Excluded(10)), 5)
+            .bucket_range(0..0, 0)
+            .sorted();
+
+        assert_eq!(result, Err(BucketError::Unsorted { index: 1 }));
+    }
+
+    #[test]
+    fn sorted_by_min_keeps_results_for_disjoint_buckets() {
+        use std::ops::Bound;
+
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(20), Some(30), 25)
+            .bucket(Some(30), None, 35)
+            .bucket_range((Bound::Excluded(10), Bound::Excluded(20)), 15)
+            .bucket(None, Some(0), -5)
+            .bucket_inclusive(Some(0), Some(10), 5);
+        let sorted = bucketizer.clone().sorted_by_min();
+
+        for input in -5..40 {
+            assert_eq!(sorted.bucketize(input), bucketizer.bucketize(input), "{}", input);
+        }
+        assert_eq!(sorted.first(), Some(&(None, Some(0), -5)));
+        assert!(sorted.sorted().is_ok());
+    }
+
+    #[test]
+    fn sorted_by_min_is_stable() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(5), Some(10), "second")
+            .bucket(Some(0), Some(10), "first")
+            .bucket(Some(5), Some(20), "third")
+            .sorted_by_min();
+
+        let values: Vec<_> = bucketizer.iter().map(|(_, _, value)| value).collect();
+        assert_eq!(values, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn normalize_drops_shadowed_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(20), Some(30), 25)
+            .bucket(Some(22), Some(28), 0)
+            .bucket(Some(0), Some(10), 5)
+            .bucket(Some(7), Some(7), 7)
+            .bucket(None, Some(0), -5)
+            .bucket(Some(10), Some(20), 15);
+        let normalized = bucketizer.normalize();
+
+        assert_eq!(normalized.iter().collect::<Vec<_>>(), vec![
+            (None, Some(0), -5),
+            (Some(0), Some(10), 5),
+            (Some(10), Some(20), 15),
+            (Some(20), Some(30), 25),
+        ]);
+        for input in -5..35 {
+            assert_eq!(normalized.bucketize(input), bucketizer.bucketize(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn normalize_keeps_order_of_overlapping_buckets() {
+        let bucketizer = Bucketizer::new()
+            .bucket(Some(10), Some(30), "wide")
+            .bucket(Some(0), Some(20), "low")
+            .bucket(Some(12), Some(18), "shadowed")
+            .bucket_with_priority(Some(14), Some(16), "priority", 1)
+            .bucket(Some(14), Some(16), "beaten");
+        let normalized = bucketizer.normalize();
+
+        let values: Vec<_> = normalized.iter().map(|(_, _, value)| value).collect();
+        assert_eq!(values, vec!["wide", "low", "priority"]);
+        for input in -5..35 {
+            assert_eq!(normalized.bucketize(input), bucketizer.bucketize(input), "{}", input);
+        }
+    }
+}
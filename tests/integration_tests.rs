@@ -1,3 +1,4 @@
+#[macro_use]
 extern crate bucketize;
 
 use bucketize::Bucketizer;
@@ -11,4 +12,32 @@ fn multiple_buckets_opened_ends() {
     assert_eq!(bucketizer.bucketize(0.0), Some(0.5));
     assert_eq!(bucketizer.bucketize(-0.7), None);
     assert_eq!(bucketizer.bucketize(1.0), Some(1.5));
-}
\ No newline at end of file
+}
+#[test]
+fn macro_matches_hand_built() {
+    let from_macro = bucketize![
+        0.0..10.0 => 5.0,
+        10.0..=20.0 => 15.0,
+        ..0.0 => -1.0,
+        20.0.. => 25.0,
+        .. => 0.0
+    ];
+    let hand_built = Bucketizer::new()
+        .bucket(Some(0.0), Some(10.0), 5.0)
+        .bucket_inclusive(Some(10.0), Some(20.0), 15.0)
+        .bucket(None, Some(0.0), -1.0)
+        .bucket(Some(20.0), None, 25.0)
+        .bucket(None, None, 0.0);
+
+    assert_eq!(from_macro, hand_built);
+    assert_eq!(from_macro.bucketize(20.0), Some(15.0));
+}
+
+#[test]
+fn macro_with_trailing_comma_and_no_buckets() {
+    let single = bucketize![0..10 => "low",];
+    let empty: Bucketizer<i32> = bucketize![];
+
+    assert_eq!(single, Bucketizer::new().bucket(Some(0), Some(10), "low"));
+    assert!(empty.is_empty());
+}
@@ -0,0 +1,27 @@
+//! Checks that the core API can be used from a `no_std` crate with only `core`
+//! and `alloc`. Build with `--no-default-features` to also check that the library
+//! itself builds without `std`.
+
+#![no_std]
+
+extern crate alloc;
+extern crate bucketize;
+
+use alloc::vec::Vec;
+
+use bucketize::Bucketizer;
+
+#[test]
+fn bucketize_without_std() {
+    let bucketizer = Bucketizer::new()
+        .bucket(None, Some(0), -1)
+        .bucket(Some(0), Some(10), 5)
+        .bucket(Some(10), None, 10);
+
+    assert_eq!(bucketizer.bucketize(-3), Some(-1));
+    assert_eq!(bucketizer.bucketize(4), Some(5));
+    assert_eq!(bucketizer.bucketize(42), Some(10));
+
+    let values: Vec<_> = [1, 11].iter().map(|&input| bucketizer.bucketize(input)).collect();
+    assert_eq!(values, [Some(5), Some(10)]);
+}